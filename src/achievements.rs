@@ -0,0 +1,118 @@
+use crate::events::GameEvent;
+
+/// What has to happen for an achievement to unlock.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Condition {
+    /// The snake grows to at least this many blocks.
+    Length(usize),
+    /// The snake fills every free cell of the board.
+    ClearBoard,
+    /// The snake stays alive for this many minutes in one game.
+    SurviveMinutes(u32),
+    /// The snake eats this much food in a row without turning left.
+    FoodWithoutLeftTurn(u32),
+}
+
+/// An achievement the player can unlock.
+pub struct Achievement {
+    /// The identifier stored in the player profile; never change it once released.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+/// Every achievement of the game. New ones only need an entry here.
+pub static ACHIEVEMENTS: [Achievement; 4] = [
+    Achievement {
+        id: "length_50",
+        name: "LONG BOY",
+        description: "REACH A LENGTH OF 50",
+        condition: Condition::Length(50),
+    },
+    Achievement {
+        id: "clear_board",
+        name: "NOWHERE LEFT",
+        description: "FILL THE WHOLE BOARD",
+        condition: Condition::ClearBoard,
+    },
+    Achievement {
+        id: "survive_5_minutes",
+        name: "SURVIVOR",
+        description: "STAY ALIVE FOR 5 MINUTES",
+        condition: Condition::SurviveMinutes(5),
+    },
+    Achievement {
+        id: "no_left_turns",
+        name: "ONLY RIGHT",
+        description: "EAT 10 FOOD WITHOUT TURNING LEFT",
+        condition: Condition::FoodWithoutLeftTurn(10),
+    },
+];
+
+/// Watches the game events and reports which achievements they satisfy.
+///
+/// The tracker keeps the little state some conditions need across events, such
+/// as the food eaten since the last left turn. It does not know which achievements
+/// are already unlocked; that is recorded in the player profile.
+pub struct AchievementTracker {
+    food_since_left_turn: u32,
+}
+
+impl AchievementTracker {
+    /// Creates a tracker for a new game.
+    pub fn new() -> AchievementTracker {
+        AchievementTracker {
+            food_since_left_turn: 0,
+        }
+    }
+
+    /// Updates the tracker with a game event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `GameEvent` emitted by the game.
+    ///
+    /// # Returns
+    ///
+    /// The achievements whose conditions are met by this event. An achievement can
+    /// be returned again by later events, so callers should ignore those already
+    /// unlocked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut tracker = AchievementTracker::new();
+    /// assert!(tracker.handle_event(&GameEvent::Restarted).is_empty());
+    /// ```
+    pub fn handle_event(&mut self, event: &GameEvent) -> Vec<&'static Achievement> {
+        match *event {
+            GameEvent::FoodEaten { .. } => self.food_since_left_turn += 1,
+            GameEvent::DirectionChanged { from, to } if from.turns_left(to) => {
+                self.food_since_left_turn = 0;
+            }
+            GameEvent::Died { .. } | GameEvent::Restarted => self.food_since_left_turn = 0,
+            _ => {}
+        }
+
+        ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| self.is_met(achievement.condition, event))
+            .collect()
+    }
+
+    /// Checks whether an event satisfies a condition.
+    fn is_met(&self, condition: Condition, event: &GameEvent) -> bool {
+        match (condition, *event) {
+            (Condition::Length(target), GameEvent::FoodEaten { length, .. }) => length >= target,
+            (Condition::ClearBoard, GameEvent::BoardCleared { .. }) => true,
+            (Condition::SurviveMinutes(target), GameEvent::Survived { minutes }) => {
+                minutes >= target
+            }
+            (Condition::FoodWithoutLeftTurn(target), GameEvent::FoodEaten { .. }) => {
+                self.food_since_left_turn >= target
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use crate::events::GameEvent;
+use crate::settings::Settings;
+
+/// The sound effects the game can play.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Sound {
+    Eat,
+    Turn,
+    Death,
+    HighScore,
+}
+
+impl Sound {
+    /// Returns the sound effect that goes with a game event, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `GameEvent` emitted by the game.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// assert!(Sound::for_event(&GameEvent::Restarted) == None);
+    /// ```
+    pub fn for_event(event: &GameEvent) -> Option<Sound> {
+        match *event {
+            GameEvent::FoodEaten { .. } | GameEvent::DroppedFoodEaten { .. } => Some(Sound::Eat),
+            GameEvent::DirectionChanged { .. } => Some(Sound::Turn),
+            GameEvent::Died { .. } => Some(Sound::Death),
+            GameEvent::NewHighScore { .. }
+            | GameEvent::BoardCleared { .. }
+            | GameEvent::LevelCompleted { .. }
+            | GameEvent::AchievementUnlocked { .. } => Some(Sound::HighScore),
+            GameEvent::TailLost { .. }
+            | GameEvent::TailShed { .. }
+            | GameEvent::Survived { .. }
+            | GameEvent::Restarted => None,
+        }
+    }
+
+    /// Returns the notes of the sound as `(frequency in Hz, duration in ms)` pairs.
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    fn notes(&self) -> &'static [(f32, u64)] {
+        match *self {
+            Sound::Eat => &[(660.0, 50), (880.0, 70)],
+            Sound::Turn => &[(440.0, 25)],
+            Sound::Death => &[(330.0, 120), (262.0, 120), (196.0, 240)],
+            Sound::HighScore => &[(523.0, 100), (659.0, 100), (784.0, 100), (1047.0, 250)],
+        }
+    }
+}
+
+/// Something that can output sound.
+pub trait AudioBackend {
+    /// Plays a sound effect once.
+    ///
+    /// # Arguments
+    ///
+    /// * `sound` - The `Sound` to play.
+    /// * `volume` - The volume, from 0 (silent) to 1 (full).
+    fn play(&mut self, sound: Sound, volume: f32);
+
+    /// Sets the volume of the looping background track.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - The volume, from 0 (silent) to 1 (full).
+    fn set_music_volume(&mut self, volume: f32);
+}
+
+/// A backend that discards all sound, used when no audio device is available.
+pub struct NullAudio;
+
+impl AudioBackend for NullAudio {
+    fn play(&mut self, _sound: Sound, _volume: f32) {}
+
+    fn set_music_volume(&mut self, _volume: f32) {}
+}
+
+/// Plays the sound effects and music of the game through an `AudioBackend`.
+pub struct Audio {
+    backend: Box<dyn AudioBackend>,
+    effects_volume: f32,
+}
+
+impl Audio {
+    /// Opens the default audio device.
+    ///
+    /// # Returns
+    ///
+    /// An `Audio` instance that starts the background track right away. If the game
+    /// was built without the `audio` feature, or there is no usable audio device,
+    /// all sound goes to `NullAudio` instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut audio = Audio::new();
+    /// audio.apply_settings(&Settings::load());
+    /// ```
+    pub fn new() -> Audio {
+        #[cfg(feature = "audio")]
+        if let Some(backend) = rodio_backend::RodioAudio::new() {
+            return Audio::with_backend(Box::new(backend));
+        }
+
+        Audio::with_backend(Box::new(NullAudio))
+    }
+
+    /// Creates an `Audio` instance that plays through the given backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The `AudioBackend` to use.
+    pub fn with_backend(backend: Box<dyn AudioBackend>) -> Audio {
+        Audio {
+            backend,
+            effects_volume: 1.0,
+        }
+    }
+
+    /// Updates the volumes from the player's settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - The current `Settings`.
+    ///
+    /// Muting silences both the sound effects and the music. This is cheap enough
+    /// to be called every frame.
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        let (effects, music) = if settings.muted {
+            (0.0, 0.0)
+        } else {
+            (
+                settings.effects_volume.fraction(),
+                settings.music_volume.fraction(),
+            )
+        };
+
+        self.effects_volume = effects;
+        self.backend.set_music_volume(music);
+    }
+
+    /// Plays the sound effect that goes with a game event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `GameEvent` emitted by the game.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut audio = Audio::new();
+    /// audio.handle_event(&GameEvent::Restarted);
+    /// ```
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        if self.effects_volume <= 0.0 {
+            return;
+        }
+
+        if let Some(sound) = Sound::for_event(event) {
+            self.backend.play(sound, self.effects_volume);
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+mod rodio_backend {
+    use super::{AudioBackend, Sound};
+    use rodio::source::{SineWave, Source};
+    use rodio::{OutputStream, OutputStreamHandle, Sink};
+    use std::time::Duration;
+
+    /// The notes of the background track, played one after the other in a loop.
+    const MUSIC_NOTES: [f32; 8] = [220.0, 277.2, 329.6, 277.2, 196.0, 246.9, 293.7, 246.9];
+    const MUSIC_NOTE_LENGTH: u64 = 250;
+
+    /// A backend that synthesizes the sounds and plays them on the default device.
+    pub struct RodioAudio {
+        _stream: OutputStream,
+        handle: OutputStreamHandle,
+        music: Sink,
+    }
+
+    impl RodioAudio {
+        /// Opens the default audio device and starts the background track.
+        ///
+        /// # Returns
+        ///
+        /// The backend, or `None` if there is no audio device or it cannot be opened.
+        pub fn new() -> Option<RodioAudio> {
+            let (stream, handle) = OutputStream::try_default().ok()?;
+            let music = Sink::try_new(&handle).ok()?;
+
+            let notes = MUSIC_NOTES
+                .iter()
+                .map(|&freq| tone(freq, MUSIC_NOTE_LENGTH, 0.1));
+            music.append(rodio::source::from_iter(notes).repeat_infinite());
+
+            Some(RodioAudio {
+                _stream: stream,
+                handle,
+                music,
+            })
+        }
+    }
+
+    impl AudioBackend for RodioAudio {
+        fn play(&mut self, sound: Sound, volume: f32) {
+            let Ok(sink) = Sink::try_new(&self.handle) else {
+                return;
+            };
+
+            sink.set_volume(volume);
+            for &(freq, millis) in sound.notes() {
+                sink.append(tone(freq, millis, 0.25));
+            }
+            sink.detach();
+        }
+
+        fn set_music_volume(&mut self, volume: f32) {
+            self.music.set_volume(volume);
+        }
+    }
+
+    /// Creates a sine tone of the given frequency, length in ms and amplitude.
+    fn tone(freq: f32, millis: u64, amplitude: f32) -> impl Source<Item = f32> + Send {
+        SineWave::new(freq)
+            .take_duration(Duration::from_millis(millis))
+            .amplify(amplitude)
+    }
+}
@@ -0,0 +1,360 @@
+use crate::level::Level;
+use crate::settings::Speed;
+use crate::storage::{key_values, read_data_file, write_data_file};
+use crate::world::World;
+use std::io;
+
+const PROGRESS_FILE: &str = "campaign.txt";
+
+/// The levels of the campaign, in the order they are played. Each is a level file with
+/// the extra `size`, `speed` and `goal` keys read by `CampaignLevel::parse`.
+const LEVELS: [&str; 5] = [FIRST_STEPS, PILLARS, WIDE_OPEN, CROSSROADS, PATROLS];
+
+const FIRST_STEPS: &str = "\
+name=first steps
+size=30x30
+speed=slow
+goal=length 8
+";
+
+const PILLARS: &str = "\
+name=pillars
+size=30x30
+speed=slow
+goal=score 10
+row=
+row=
+row=
+row=
+row=
+row=.......##........##........
+row=.......##........##........
+row=
+row=
+row=
+row=
+row=
+row=
+row=.......##........##........
+row=.......##........##........
+row=
+row=
+row=
+row=
+row=
+row=.......##........##........
+row=.......##........##........
+";
+
+const WIDE_OPEN: &str = "\
+name=wide open
+size=40x30
+speed=normal
+goal=score 25
+foods=normal,bonus
+row=
+row=
+row=
+row=
+row=
+row=
+row=
+row=
+row=
+row=.........####################
+row=
+row=
+row=
+row=
+row=
+row=
+row=.........####################
+";
+
+const CROSSROADS: &str = "\
+name=crossroads
+size=30x30
+speed=normal
+goal=length 15
+row=
+row=
+row=
+row=
+row=
+row=.............#
+row=.............#
+row=.............#
+row=.............#
+row=.............#
+row=....A........#..........B
+row=.............#
+row=.............#
+row=.............#
+row=########.#########.#######
+row=.............#
+row=.............#
+row=....B........#..........A
+row=.............#
+row=.............#
+row=.............#
+row=.............#
+";
+
+const PATROLS: &str = "\
+name=patrols
+size=36x36
+speed=fast
+goal=score 20
+foods=normal,bonus
+row=
+row=
+row=
+row=
+row=
+row=
+row=..........-
+row=
+row=
+row=.....######........######
+row=
+row=
+row=
+row=
+row=.................*
+row=
+row=
+row=
+row=.....######........######
+row=
+row=
+row=
+row=
+row=..........................|
+row=
+row=
+row=
+row=
+row=..............................@
+";
+
+/// What the player has to reach on a campaign level to move on to the next one.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Goal {
+    /// The snake has to grow to this many blocks.
+    Length(usize),
+    /// The score has to reach this many points.
+    Score(u32),
+}
+
+impl Goal {
+    /// Parses a goal written as `length N` or `score N`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The goal as written in a campaign level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// assert!(Goal::parse("length 12") == Some(Goal::Length(12)));
+    /// assert!(Goal::parse("speed 3").is_none());
+    /// ```
+    fn parse(value: &str) -> Option<Goal> {
+        let (kind, target) = value.split_once(' ')?;
+        let target = target.trim();
+        match kind {
+            "length" => target.parse().ok().map(Goal::Length),
+            "score" => target.parse().ok().map(Goal::Score),
+            _ => None,
+        }
+    }
+
+    /// Checks whether a world has reached the goal.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` of the game being played.
+    pub fn is_reached(&self, world: &World) -> bool {
+        match *self {
+            Goal::Length(target) => world.snake.len() >= target,
+            Goal::Score(target) => world.score >= target,
+        }
+    }
+
+    /// Returns how far a world is toward the goal, as shown while playing.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` of the game being played.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let world = World::new(30, 30, Grid::Square, 42, Layout::default());
+    /// assert_eq!(Goal::Length(10).progress(&world), "LENGTH 3/10");
+    /// ```
+    pub fn progress(&self, world: &World) -> String {
+        match *self {
+            Goal::Length(target) => format!("LENGTH {}/{}", world.snake.len(), target),
+            Goal::Score(target) => format!("SCORE {}/{}", world.score, target),
+        }
+    }
+
+    /// Returns the goal as shown on the campaign screen.
+    pub fn describe(&self) -> String {
+        match *self {
+            Goal::Length(target) => format!("LENGTH {}", target),
+            Goal::Score(target) => format!("SCORE {}", target),
+        }
+    }
+}
+
+/// A level of the campaign, along with the board size, the speed and the goal it is
+/// played with.
+pub struct CampaignLevel {
+    pub level: Level,
+    pub width: i32,
+    pub height: i32,
+    pub speed: Speed,
+    pub goal: Goal,
+}
+
+impl CampaignLevel {
+    /// Parses a campaign level.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The level, in the format read by `Level::parse`.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `CampaignLevel`, or a message describing what is wrong with it.
+    ///
+    /// On top of the keys of a level file, `size` is the `WxH` size of the board with
+    /// its walls, `speed` the name of the speed the snake moves at, and `goal` either
+    /// `length N` or `score N`. The goal is required; the board is 30x30 and the speed
+    /// normal by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let level = CampaignLevel::parse("name=tiny\nsize=40x30\ngoal=score 5").unwrap();
+    /// assert_eq!((level.width, level.height), (40, 30));
+    /// assert!(level.goal == Goal::Score(5));
+    /// ```
+    pub fn parse(text: &str) -> Result<CampaignLevel, String> {
+        let mut size = (30, 30);
+        let mut speed = Speed::Normal;
+        let mut goal = None;
+
+        for (key, value) in key_values(text) {
+            match key {
+                "size" => size = parse_size(value).ok_or("the size is not WxH")?,
+                "speed" => speed = Speed::from_name(value).ok_or("unknown speed")?,
+                "goal" => goal = Some(Goal::parse(value).ok_or("unknown goal")?),
+                _ => {}
+            }
+        }
+
+        let (width, height) = size;
+        Ok(CampaignLevel {
+            level: Level::parse(text, width, height)?,
+            width,
+            height,
+            speed,
+            goal: goal.ok_or("the level has no goal")?,
+        })
+    }
+}
+
+/// The campaign: a fixed sequence of levels, each unlocked by reaching the goal of the
+/// one before it.
+///
+/// How many levels have been completed is kept in `campaign.txt` in the data directory.
+/// The first level is always unlocked.
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+    completed: usize,
+}
+
+impl Campaign {
+    /// Loads the campaign levels and the player's progress through them.
+    ///
+    /// # Returns
+    ///
+    /// A `Campaign`. A missing or unreadable progress file leaves only the first level
+    /// unlocked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let campaign = Campaign::load();
+    /// assert!(campaign.is_unlocked(0));
+    /// ```
+    pub fn load() -> Campaign {
+        let levels: Vec<CampaignLevel> = LEVELS
+            .iter()
+            .filter_map(|text| CampaignLevel::parse(text).ok())
+            .collect();
+
+        let text = read_data_file(PROGRESS_FILE).unwrap_or_default();
+        let completed = key_values(&text)
+            .find(|&(key, _)| key == "completed")
+            .and_then(|(_, value)| value.parse().ok())
+            .unwrap_or(0)
+            .min(levels.len());
+
+        Campaign { levels, completed }
+    }
+
+    /// Saves the player's progress to the data directory.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if the file could not be written.
+    pub fn save(&self) -> io::Result<()> {
+        write_data_file(PROGRESS_FILE, format!("completed={}\n", self.completed))
+    }
+
+    /// Checks whether a level can be played yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the level in the campaign.
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index <= self.completed
+    }
+
+    /// Checks whether the goal of a level has been reached before.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the level in the campaign.
+    pub fn is_completed(&self, index: usize) -> bool {
+        index < self.completed
+    }
+
+    /// Records that the goal of a level was reached, unlocking the next one.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the completed level.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the level had not been completed before, so the progress needs saving.
+    pub fn complete(&mut self, index: usize) -> bool {
+        if self.is_completed(index) {
+            return false;
+        }
+
+        self.completed = index + 1;
+        true
+    }
+}
+
+/// Parses a board size written as `WxH`.
+fn parse_size(value: &str) -> Option<(i32, i32)> {
+    let (width, height) = value.split_once('x')?;
+    Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+}
@@ -0,0 +1,401 @@
+use crate::level::Layout;
+use crate::settings::Speed;
+use crate::storage::{read_data_file, write_data_file};
+use crate::topology::Grid;
+use crate::world::World;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LEADERBOARD_FILE: &str = "daily_scores.json";
+const MAX_ENTRIES: usize = 10;
+
+/// The width and height of the board the daily challenge is played on, including the
+/// walls, whatever the size of the window.
+pub const DAILY_SIZE: (i32, i32) = (30, 30);
+
+/// Mixed into the seed for the obstacle layout, so that it does not use the same
+/// random numbers as the food.
+const LAYOUT_SALT: u64 = 0x5eed_1a70_da11_c0de;
+
+/// Obstacles are kept out of the cells with `x <= SAFE_X` and `y <= SAFE_Y`, where the
+/// snake and the first food start.
+const SAFE_X: i32 = 10;
+const SAFE_Y: i32 = 5;
+
+/// The game everyone plays on a given day.
+///
+/// The seed, the speed and the obstacle layout are all derived from the date, so
+/// every copy of the game produces the same challenge on the same day.
+pub struct DailyChallenge {
+    /// The day, counted in days since 1970-01-01 (UTC).
+    pub day: i64,
+    pub seed: u64,
+    pub speed: Speed,
+    pub obstacles: Vec<(i32, i32)>,
+}
+
+impl DailyChallenge {
+    /// Returns the challenge of the current day, in UTC.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    pub fn today(width: i32, height: i32) -> DailyChallenge {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        DailyChallenge::for_day((seconds / 86_400) as i64, width, height)
+    }
+
+    /// Returns the challenge of a given day.
+    ///
+    /// # Arguments
+    ///
+    /// * `day` - The day, counted in days since 1970-01-01.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// # Returns
+    ///
+    /// The `DailyChallenge` of that day. The same arguments always give the same
+    /// challenge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let day = parse_date("2024-03-01").unwrap();
+    /// let a = DailyChallenge::for_day(day, 30, 30);
+    /// let b = DailyChallenge::for_day(day, 30, 30);
+    /// assert_eq!(a.obstacles, b.obstacles);
+    /// ```
+    pub fn for_day(day: i64, width: i32, height: i32) -> DailyChallenge {
+        let seed = split_mix(day as u64);
+        let mut rng = Pcg32::seed_from_u64(seed ^ LAYOUT_SALT);
+
+        let speed = Speed::ALL[rng.gen_range(0..Speed::ALL.len())];
+        let obstacles = generate_obstacles(&mut rng, width, height);
+
+        DailyChallenge {
+            day,
+            seed,
+            speed,
+            obstacles,
+        }
+    }
+
+    /// Returns the date of the challenge as `YYYY-MM-DD`.
+    pub fn date(&self) -> String {
+        format_date(self.day)
+    }
+
+    /// Returns the layout of the board, which has the obstacles of the day and no
+    /// portals or enemies.
+    pub fn layout(&self) -> Layout {
+        Layout {
+            obstacles: self.obstacles.clone(),
+            ..Layout::default()
+        }
+    }
+
+    /// Creates the world the challenge starts from.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    pub fn world(&self, width: i32, height: i32) -> World {
+        World::new(width, height, Grid::Square, self.seed, self.layout())
+    }
+}
+
+/// The outcome of one daily challenge run.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub score: u32,
+    pub length: usize,
+    pub ticks: u32,
+}
+
+/// The best daily challenge results on this machine, per day.
+#[derive(Default, Serialize, Deserialize)]
+pub struct DailyLeaderboard {
+    days: BTreeMap<String, Vec<DailyResult>>,
+}
+
+impl DailyLeaderboard {
+    /// Loads the leaderboard from the data directory.
+    ///
+    /// # Returns
+    ///
+    /// A `DailyLeaderboard` instance. A missing file gives an empty leaderboard; a file
+    /// that cannot be parsed is reported on the console and ignored.
+    pub fn load() -> DailyLeaderboard {
+        let Some(text) = read_data_file(LEADERBOARD_FILE) else {
+            return DailyLeaderboard::default();
+        };
+
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            eprintln!("failed to read {}: {}", LEADERBOARD_FILE, e);
+            DailyLeaderboard::default()
+        })
+    }
+
+    /// Saves the leaderboard to the data directory.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if the file could not be written.
+    pub fn save(&self) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        write_data_file(LEADERBOARD_FILE, text)
+    }
+
+    /// Records a result if it is good enough to enter the table of its day.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date of the challenge, as `YYYY-MM-DD`.
+    /// * `result` - The `DailyResult` of the run.
+    ///
+    /// # Returns
+    ///
+    /// The zero-based rank of the new entry among the results of that day, or `None`
+    /// if it did not make it into the table. Equal scores rank below earlier ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut leaderboard = DailyLeaderboard::default();
+    /// let result = DailyResult { score: 7, length: 10, ticks: 120 };
+    /// assert_eq!(leaderboard.submit("2024-03-01", result), Some(0));
+    /// ```
+    pub fn submit(&mut self, date: &str, result: DailyResult) -> Option<usize> {
+        let results = self.days.entry(date.to_string()).or_default();
+        let rank = results
+            .iter()
+            .position(|r| result.score > r.score)
+            .unwrap_or(results.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+
+        results.insert(rank, result);
+        results.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// Parses a date written as `YYYY-MM-DD`.
+///
+/// # Arguments
+///
+/// * `text` - The date to parse.
+///
+/// # Returns
+///
+/// The day counted in days since 1970-01-01, or `None` if the text is not a valid date.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(parse_date("1970-01-02"), Some(1));
+/// assert_eq!(parse_date("2024-02-30"), None);
+/// ```
+pub fn parse_date(text: &str) -> Option<i64> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    (civil_from_days(days) == (year, month, day)).then_some(days)
+}
+
+/// Formats a day counted in days since 1970-01-01 as `YYYY-MM-DD`.
+pub fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Converts a date of the proleptic Gregorian calendar into days since 1970-01-01.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since 1970-01-01 into a `(year, month, day)` date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Scrambles a number into a well distributed seed.
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Places a few short straight obstacles on the board.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator of the layout.
+/// * `width` - The width of the board, including the walls.
+/// * `height` - The height of the board, including the walls.
+///
+/// # Returns
+///
+/// The cells of the obstacles. Obstacles keep a free cell between themselves, the walls
+/// and each other, so they never close off part of the board, and they stay away from
+/// where the snake and the first food start.
+fn generate_obstacles(rng: &mut Pcg32, width: i32, height: i32) -> Vec<(i32, i32)> {
+    let mut cells: Vec<(i32, i32)> = Vec::new();
+    let count = rng.gen_range(3..=6);
+
+    let mut placed = 0;
+    for _ in 0..100 {
+        if placed == count {
+            break;
+        }
+
+        let length = rng.gen_range(3..=6);
+        let (dx, dy) = if rng.gen_bool(0.5) { (1, 0) } else { (0, 1) };
+        let max_x = width - 2 - dx * (length - 1);
+        let max_y = height - 2 - dy * (length - 1);
+        if max_x <= 2 || max_y <= 2 {
+            continue;
+        }
+
+        let (x, y) = (rng.gen_range(2..max_x), rng.gen_range(2..max_y));
+        let line: Vec<(i32, i32)> = (0..length).map(|i| (x + dx * i, y + dy * i)).collect();
+
+        let blocked = line.iter().any(|&(x, y)| {
+            (x <= SAFE_X && y <= SAFE_Y)
+                || cells
+                    .iter()
+                    .any(|&(cx, cy)| (cx - x).abs() <= 1 && (cy - y).abs() <= 1)
+        });
+        if !blocked {
+            cells.extend(line);
+            placed += 1;
+        }
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch_is_day_zero() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+    }
+
+    #[test]
+    fn leap_days_follow_the_gregorian_rules() {
+        assert_eq!(parse_date("2000-02-29"), Some(11_016));
+        assert_eq!(parse_date("2000-03-01"), Some(11_017));
+        assert!(parse_date("2024-02-29").is_some());
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2100-02-29"), None);
+        assert_eq!(format_date(11_016), "2000-02-29");
+    }
+
+    #[test]
+    fn days_before_the_epoch_are_negative() {
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("1900-01-01"), Some(-25_567));
+        assert_eq!(format_date(-1), "1969-12-31");
+        assert_eq!(civil_from_days(-25_567), (1900, 1, 1));
+    }
+
+    #[test]
+    fn malformed_dates_are_rejected() {
+        for text in [
+            "",
+            "2024",
+            "2024-03",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-04-31",
+            "x-1-1",
+        ] {
+            assert_eq!(parse_date(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn dates_round_trip() {
+        for days in (-700_000..700_000).step_by(997) {
+            assert_eq!(parse_date(&format_date(days)), Some(days));
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn split_mix_scrambles_neighbors() {
+        assert_eq!(split_mix(0), 0xe220_a839_7b1d_cdaf);
+        assert_ne!(split_mix(1), split_mix(2));
+    }
+
+    #[test]
+    fn challenge_depends_only_on_the_day() {
+        let (width, height) = DAILY_SIZE;
+        let a = DailyChallenge::for_day(19_783, width, height);
+        let b = DailyChallenge::for_day(19_783, width, height);
+        assert_eq!(a.seed, b.seed);
+        assert!(a.speed == b.speed);
+        assert_eq!(a.obstacles, b.obstacles);
+        assert_ne!(a.seed, DailyChallenge::for_day(19_784, width, height).seed);
+    }
+
+    #[test]
+    fn obstacles_stay_inside_and_away_from_the_start() {
+        let (width, height) = DAILY_SIZE;
+        for day in 19_000..19_100 {
+            let challenge = DailyChallenge::for_day(day, width, height);
+            for &(x, y) in &challenge.obstacles {
+                assert!(x > 1 && y > 1 && x < width - 2 && y < height - 2);
+                assert!(x > SAFE_X || y > SAFE_Y);
+            }
+            assert!(challenge
+                .world(width, height)
+                .validate(width, height)
+                .is_ok());
+        }
+    }
+}
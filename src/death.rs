@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What the snake ran into.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DeathCause {
+    /// The snake hit the border of the board.
+    Wall,
+    /// The snake bit its own body; `segment` counts from the head, which is 0.
+    OwnBody { segment: usize },
+    /// The snake ran into an obstacle inside the walls.
+    Obstacle,
+    /// The snake's head met an enemy.
+    Enemy,
+    /// The time of a time attack game ran out.
+    TimeUp,
+    /// The closing walls of survival caught the snake.
+    Crushed,
+    /// The snake went hungry at its shortest and had no tail left to lose.
+    Starved,
+}
+
+/// How and where a game ended.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Death {
+    pub cause: DeathCause,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl DeathCause {
+    /// Returns the short name of the kind of cause, without details such as the
+    /// segment, used to group deaths in the statistics.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            DeathCause::Wall => "WALL",
+            DeathCause::OwnBody { .. } => "SELF",
+            DeathCause::Obstacle => "OBSTACLE",
+            DeathCause::Enemy => "ENEMY",
+            DeathCause::TimeUp => "TIME",
+            DeathCause::Crushed => "CRUSHED",
+            DeathCause::Starved => "STARVED",
+        }
+    }
+}
+
+impl fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeathCause::Wall => write!(f, "hit the wall"),
+            DeathCause::OwnBody { segment } => write!(f, "bit itself at segment {}", segment),
+            DeathCause::Obstacle => write!(f, "hit an obstacle"),
+            DeathCause::Enemy => write!(f, "was caught by an enemy"),
+            DeathCause::TimeUp => write!(f, "ran out of time"),
+            DeathCause::Crushed => write!(f, "was crushed by the walls"),
+            DeathCause::Starved => write!(f, "starved"),
+        }
+    }
+}
+
+impl fmt::Display for Death {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at ({}, {})", self.cause, self.x, self.y)
+    }
+}
@@ -1,7 +1,9 @@
+use crate::font::{glyph, GLYPH_HEIGHT, GLYPH_SPACING, GLYPH_WIDTH};
 use piston_window::types::Color;
-use piston_window::{rectangle, Context, G2d};
+use piston_window::{line, polygon, rectangle, Context, G2d, Rectangle, Transformed};
 
-const BLOCK_SIZE: f64 = 25.0;
+pub const BLOCK_SIZE: f64 = 25.0;
+const MAX_WINDOW_SIZE: f64 = 900.0;
 
 /// Converts game coordinates to screen coordinates.
 ///
@@ -22,7 +24,8 @@ const BLOCK_SIZE: f64 = 25.0;
 ///
 /// This function takes a game coordinate (which is an integer value) and
 /// multiplies it by the constant `BLOCK_SIZE` (25.0) to convert it into
-/// screen coordinates.
+/// screen coordinates. These are the pixels of a window that fits the board
+/// exactly; `board_context` scales them to the actual size of the window.
 pub fn to_coord(game_coord: i32) -> f64 {
     (game_coord as f64) * BLOCK_SIZE
 }
@@ -51,46 +54,251 @@ pub fn to_coord_u32(game_coord: i32) -> u32 {
     to_coord(game_coord) as u32
 }
 
-/// Draws a block on the screen at the specified game coordinates.
+/// Computes the initial window size for a board of the given dimensions.
+///
+/// # Arguments
+///
+/// * `width` - The width of the board in blocks.
+/// * `height` - The height of the board in blocks.
+///
+/// # Returns
+///
+/// An array `[width, height]` with the window size in pixels.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(window_size(30, 30), [750, 750]);
+/// assert_eq!(window_size(100, 50), [900, 450]);
+/// ```
+///
+/// Boards that fit into `MAX_WINDOW_SIZE` get `BLOCK_SIZE` pixels per block.
+/// Larger boards are scaled down so that their longest side is `MAX_WINDOW_SIZE`
+/// pixels, which keeps the window on the screen.
+pub fn window_size(width: i32, height: i32) -> [u32; 2] {
+    let longest = to_coord(width.max(height));
+    if longest <= MAX_WINDOW_SIZE {
+        return [to_coord_u32(width), to_coord_u32(height)];
+    }
+
+    let scale = MAX_WINDOW_SIZE / longest;
+    [
+        (to_coord(width) * scale) as u32,
+        (to_coord(height) * scale) as u32,
+    ]
+}
+
+/// Creates a drawing context that fits the board into the current window.
+///
+/// # Arguments
+///
+/// * `con` - A reference to the window's `Context`.
+/// * `width` - The width of the board in blocks.
+/// * `height` - The height of the board in blocks.
+///
+/// # Returns
+///
+/// A `Context` in which the board drawn with `draw_block_at` and friends is
+/// scaled to the largest cell size that fits and centered in the window.
+///
+/// # Example
+///
+/// ```rust
+/// // Assuming you have a valid Context and G2d instance:
+/// // let board = board_context(&con, 30, 30);
+/// // game.draw(&board, &mut g);
+/// ```
+///
+/// The cell size is computed from the window's view size every frame, so the
+/// board follows resizing and fullscreen changes. If the aspect ratio of the
+/// window differs from the board's, the leftover space is split evenly on both
+/// sides (letterboxing).
+pub fn board_context(con: &Context, width: i32, height: i32) -> Context {
+    let (offset_x, offset_y, cell_size) = board_placement(con.get_view_size(), width, height);
+    con.trans(offset_x, offset_y).zoom(cell_size / BLOCK_SIZE)
+}
+
+/// Turns a position in the window into a position on the board, the inverse of the
+/// transformation made by `board_context`.
+///
+/// # Arguments
+///
+/// * `position` - The position in the window, such as the mouse cursor.
+/// * `view_size` - The size of the window.
+/// * `width` - The width of the board in blocks.
+/// * `height` - The height of the board in blocks.
+///
+/// # Returns
+///
+/// The position on the board, in blocks.
+///
+/// # Example
+///
+/// ```rust
+/// let position = board_position([450.0, 300.0], [900.0, 600.0], 30, 30);
+/// assert_eq!(position, [15.0, 15.0]);
+/// ```
+pub fn board_position(
+    position: [f64; 2],
+    view_size: [f64; 2],
+    width: i32,
+    height: i32,
+) -> [f64; 2] {
+    let (offset_x, offset_y, cell_size) = board_placement(view_size, width, height);
+    [
+        (position[0] - offset_x) / cell_size,
+        (position[1] - offset_y) / cell_size,
+    ]
+}
+
+/// Returns where the board is placed in a window: the offset of its top-left corner and
+/// the size of a cell, so that it fills as much of the window as it can, centered.
+fn board_placement(view_size: [f64; 2], width: i32, height: i32) -> (f64, f64, f64) {
+    let [view_width, view_height] = view_size;
+    let cell_size = (view_width / width as f64).min(view_height / height as f64);
+
+    let offset_x = (view_width - cell_size * width as f64) / 2.0;
+    let offset_y = (view_height - cell_size * height as f64) / 2.0;
+    (offset_x, offset_y, cell_size)
+}
+
+/// Draws a block on the screen at fractional game coordinates.
 ///
 /// # Arguments
 ///
 /// * `color` - A `Color` representing the color of the block.
-/// * `x` - An integer representing the x-coordinate in the game's grid.
-/// * `y` - An integer representing the y-coordinate in the game's grid.
+/// * `x` - The x-coordinate in the game's grid, which may lie between two cells.
+/// * `y` - The y-coordinate in the game's grid, which may lie between two cells.
 /// * `con` - A reference to the `Context` for rendering.
 /// * `g` - A mutable reference to the `G2d` graphics backend.
 ///
 /// # Example
 ///
 /// ```rust
-/// use piston_window::types::Color;
-/// use piston_window::{Context, G2d};
+/// // Assuming you have a valid Context and G2d instance:
+/// // draw_block_at([0.0, 1.0, 0.0, 1.0], 2.5, 3.0, &con, &mut g);
+/// ```
+///
+/// The coordinates are those of the top-left corner of the block, so blocks that
+/// are sliding from one cell to the next can be drawn too.
+pub fn draw_block_at(color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+    rectangle(
+        color,
+        [x * BLOCK_SIZE, y * BLOCK_SIZE, BLOCK_SIZE, BLOCK_SIZE],
+        con.transform,
+        g,
+    );
+}
+
+/// Draws the outline of a block on the screen at fractional game coordinates.
 ///
-/// let color: Color = [1.0, 0.0, 0.0, 1.0]; // Red color
-/// let x = 2;
-/// let y = 3;
+/// # Arguments
+///
+/// * `color` - A `Color` representing the color of the outline.
+/// * `x` - The x-coordinate of the top-left corner of the block, in blocks.
+/// * `y` - The y-coordinate of the top-left corner of the block, in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// # Example
 ///
+/// ```rust
 /// // Assuming you have a valid Context and G2d instance:
-/// // draw_block(color, x, y, &con, &mut g);
+/// // draw_block_outline_at([1.0, 1.0, 0.0, 1.0], 2.0, 3.0, &con, &mut g);
 /// ```
 ///
-/// This function draws a block of the specified color at the given (x, y)
-/// game coordinates. It converts the game coordinates to screen coordinates
-/// using the `to_coord` function and then uses the `rectangle` function
-/// from the `piston_window` crate to draw the block.
-pub fn draw_block(color: Color, x: i32, y: i32, con: &Context, g: &mut G2d) {
-    let gui_x = to_coord(x);
-    let gui_y = to_coord(y);
+/// The outline is drawn inside the block, so it does not cover its neighbours.
+pub fn draw_block_outline_at(color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+    let radius = BLOCK_SIZE / 10.0;
 
-    rectangle(
-        color,
-        [gui_x, gui_y, BLOCK_SIZE, BLOCK_SIZE],
+    Rectangle::new_border(color, radius).draw(
+        [
+            x * BLOCK_SIZE + radius,
+            y * BLOCK_SIZE + radius,
+            BLOCK_SIZE - radius * 2.0,
+            BLOCK_SIZE - radius * 2.0,
+        ],
+        &con.draw_state,
         con.transform,
         g,
     );
 }
 
+/// Returns the corners of a hexagon that fills a block from side to side and
+/// sticks out by a sixth of a block above and below it.
+///
+/// # Arguments
+///
+/// * `x` - The x-coordinate of the top-left corner of the block, in blocks.
+/// * `y` - The y-coordinate of the top-left corner of the block, in blocks.
+/// * `scale` - How large the hexagon is compared to a full cell, from 0 to 1.
+///
+/// # Returns
+///
+/// The six corners in screen coordinates, clockwise from the top one.
+///
+/// Rows of such hexagons one block apart, with every other row shifted by half a
+/// block, tile the plane.
+fn hex_corners(x: f64, y: f64, scale: f64) -> [[f64; 2]; 6] {
+    let center_x = (x + 0.5) * BLOCK_SIZE;
+    let center_y = (y + 0.5) * BLOCK_SIZE;
+    let half_width = BLOCK_SIZE / 2.0 * scale;
+    let tip = BLOCK_SIZE * 2.0 / 3.0 * scale;
+    let side = BLOCK_SIZE / 3.0 * scale;
+
+    [
+        [center_x, center_y - tip],
+        [center_x + half_width, center_y - side],
+        [center_x + half_width, center_y + side],
+        [center_x, center_y + tip],
+        [center_x - half_width, center_y + side],
+        [center_x - half_width, center_y - side],
+    ]
+}
+
+/// Draws a hexagonal cell on the screen at fractional game coordinates.
+///
+/// # Arguments
+///
+/// * `color` - A `Color` representing the color of the cell.
+/// * `x` - The x-coordinate of the top-left corner of the cell's block, in blocks.
+/// * `y` - The y-coordinate of the top-left corner of the cell's block, in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// # Example
+///
+/// ```rust
+/// // Assuming you have a valid Context and G2d instance:
+/// // draw_hex_at([0.0, 1.0, 0.0, 1.0], 2.25, 3.0, &con, &mut g);
+/// ```
+///
+/// The hexagon points up and down, see `hex_corners`.
+pub fn draw_hex_at(color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+    polygon(color, &hex_corners(x, y, 1.0), con.transform, g);
+}
+
+/// Draws the outline of a hexagonal cell on the screen at fractional game coordinates.
+///
+/// # Arguments
+///
+/// * `color` - A `Color` representing the color of the outline.
+/// * `x` - The x-coordinate of the top-left corner of the cell's block, in blocks.
+/// * `y` - The y-coordinate of the top-left corner of the cell's block, in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// Like `draw_block_outline_at`, the outline is drawn inside the cell.
+pub fn draw_hex_outline_at(color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+    let radius = BLOCK_SIZE / 20.0;
+    let corners = hex_corners(x, y, 0.85);
+
+    for (index, &[x1, y1]) in corners.iter().enumerate() {
+        let [x2, y2] = corners[(index + 1) % corners.len()];
+        line(color, radius, [x1, y1, x2, y2], con.transform, g);
+    }
+}
+
 /// Draws a rectangle on the screen at the specified game coordinates.
 ///
 /// # Arguments
@@ -147,3 +355,156 @@ pub fn draw_rectangle(
         g,
     );
 }
+
+/// Draws a vertical bar standing on a baseline, for charts.
+///
+/// # Arguments
+///
+/// * `color` - A `Color` representing the color of the bar.
+/// * `x` - The x-coordinate of the left edge of the bar, in blocks.
+/// * `bottom` - The y-coordinate of the baseline, in blocks.
+/// * `width` - The width of the bar, in blocks.
+/// * `height` - The height of the bar above the baseline, in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// # Example
+///
+/// ```rust
+/// // Assuming you have a valid Context and G2d instance:
+/// // draw_bar([1.0, 1.0, 1.0, 1.0], 5.0, 26.0, 0.8, 3.5, &con, &mut g);
+/// ```
+pub fn draw_bar(
+    color: Color,
+    x: f64,
+    bottom: f64,
+    width: f64,
+    height: f64,
+    con: &Context,
+    g: &mut G2d,
+) {
+    rectangle(
+        color,
+        [
+            x * BLOCK_SIZE,
+            (bottom - height) * BLOCK_SIZE,
+            width * BLOCK_SIZE,
+            height * BLOCK_SIZE,
+        ],
+        con.transform,
+        g,
+    );
+}
+
+/// Returns the width of a line of text drawn with `draw_text`.
+///
+/// # Arguments
+///
+/// * `text` - The text to measure.
+/// * `size` - The height of the text in blocks.
+///
+/// # Returns
+///
+/// A floating-point number representing the width of the text in blocks.
+///
+/// # Example
+///
+/// ```rust
+/// let width = text_width("SNAKE", 1.0);
+/// assert!(width > 3.0);
+/// ```
+///
+/// Every glyph is `GLYPH_WIDTH` font pixels wide followed by `GLYPH_SPACING`
+/// pixels of space, except the last one which has no trailing space.
+pub fn text_width(text: &str, size: f64) -> f64 {
+    let pixel = size / GLYPH_HEIGHT as f64;
+    let count = text.chars().count();
+    if count == 0 {
+        return 0.0;
+    }
+
+    let pixels = count * (GLYPH_WIDTH + GLYPH_SPACING) - GLYPH_SPACING;
+    pixels as f64 * pixel
+}
+
+/// Draws a line of text on the screen using the built-in bitmap font.
+///
+/// # Arguments
+///
+/// * `color` - A `Color` representing the color of the text.
+/// * `text` - The text to draw.
+/// * `x` - The x-coordinate of the left edge of the text in the game's grid.
+/// * `y` - The y-coordinate of the top edge of the text in the game's grid.
+/// * `size` - The height of the text in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// # Example
+///
+/// ```rust
+/// // Assuming you have a valid Context and G2d instance:
+/// // draw_text([1.0, 1.0, 1.0, 1.0], "GAME OVER", 2.0, 5.0, 2.0, &con, &mut g);
+/// ```
+///
+/// Coordinates are given in blocks but may be fractional, so text can be
+/// placed independently of the grid. Each lit pixel of a glyph is drawn as a
+/// small square using the `rectangle` function.
+pub fn draw_text(color: Color, text: &str, x: f64, y: f64, size: f64, con: &Context, g: &mut G2d) {
+    let pixel = size / GLYPH_HEIGHT as f64 * BLOCK_SIZE;
+    let left = x * BLOCK_SIZE;
+    let top = y * BLOCK_SIZE;
+
+    for (index, c) in text.chars().enumerate() {
+        let offset = (index * (GLYPH_WIDTH + GLYPH_SPACING)) as f64 * pixel;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+
+                rectangle(
+                    color,
+                    [
+                        left + offset + col as f64 * pixel,
+                        top + row as f64 * pixel,
+                        pixel,
+                        pixel,
+                    ],
+                    con.transform,
+                    g,
+                );
+            }
+        }
+    }
+}
+
+/// Draws a line of text horizontally centered on the given x-coordinate.
+///
+/// # Arguments
+///
+/// * `color` - A `Color` representing the color of the text.
+/// * `text` - The text to draw.
+/// * `center_x` - The x-coordinate of the center of the text in the game's grid.
+/// * `y` - The y-coordinate of the top edge of the text in the game's grid.
+/// * `size` - The height of the text in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// # Example
+///
+/// ```rust
+/// // Assuming you have a valid Context and G2d instance:
+/// // draw_text_centered([1.0, 1.0, 1.0, 1.0], "PAUSED", 15.0, 10.0, 1.0, &con, &mut g);
+/// ```
+pub fn draw_text_centered(
+    color: Color,
+    text: &str,
+    center_x: f64,
+    y: f64,
+    size: f64,
+    con: &Context,
+    g: &mut G2d,
+) {
+    let x = center_x - text_width(text, size) / 2.0;
+    draw_text(color, text, x, y, size, con, g);
+}
@@ -0,0 +1,332 @@
+use crate::level::{Layout, Level, Portal, MAX_PORTALS};
+use crate::world::World;
+use std::io;
+
+/// What painting a cell in the level editor does.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Tool {
+    /// Places an obstacle.
+    Wall,
+    /// Places one tile of a portal pair; the pair is finished with the next tile.
+    Portal,
+    /// Moves the snake's starting position, with its tail on the cell.
+    Spawn,
+    /// Marks a cell where food may appear.
+    Food,
+    /// Clears the cell, removing a whole portal pair at once.
+    Erase,
+}
+
+impl Tool {
+    /// All tools, in the order of their number keys.
+    pub const ALL: [Tool; 5] = [
+        Tool::Wall,
+        Tool::Portal,
+        Tool::Spawn,
+        Tool::Food,
+        Tool::Erase,
+    ];
+
+    /// Returns the name of the tool as shown in the editor.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Tool::Wall => "WALL",
+            Tool::Portal => "PORTAL",
+            Tool::Spawn => "SPAWN",
+            Tool::Food => "FOOD",
+            Tool::Erase => "ERASE",
+        }
+    }
+
+    /// Returns whether dragging the mouse keeps painting with the tool, rather than
+    /// only the cell the button was pressed on.
+    pub fn paints_while_dragging(&self) -> bool {
+        matches!(*self, Tool::Wall | Tool::Food | Tool::Erase)
+    }
+}
+
+/// A level being edited, with the history needed to undo and redo changes.
+///
+/// Every mouse stroke is one step in the history, however many cells it paints.
+pub struct Editor {
+    pub level: Level,
+    pub tool: Tool,
+    /// The first tile of a portal pair that is waiting for its second tile.
+    pub pending_portal: Option<(i32, i32)>,
+    /// A message about the last save, shown until the next change.
+    pub message: Option<String>,
+    undo: Vec<Layout>,
+    redo: Vec<Layout>,
+    stroke_recorded: bool,
+    /// The level as it was when last saved, or when opened if it has not been saved.
+    saved: Level,
+    /// Whether the last save found a file of the same name, so the next one replaces it.
+    confirm_overwrite: bool,
+    /// Whether the level was saved from this editor, so its file is replaced without
+    /// asking again.
+    written: bool,
+    /// Whether leaving was asked for with unsaved changes, so asking again discards them.
+    confirm_leave: bool,
+}
+
+impl Editor {
+    /// Starts editing a level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The `Level` to edit, which is only saved on request.
+    pub fn new(level: Level) -> Editor {
+        Editor {
+            saved: level.clone(),
+            level,
+            tool: Tool::Wall,
+            pending_portal: None,
+            message: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            stroke_recorded: false,
+            confirm_overwrite: false,
+            written: false,
+            confirm_leave: false,
+        }
+    }
+
+    /// Returns the world a game on the level starts with, to draw the level with.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    pub fn preview(&self, width: i32, height: i32) -> World {
+        World::new(width, height, self.level.grid, 0, self.level.layout.clone())
+    }
+
+    /// Starts a new mouse stroke, which is undone as a whole.
+    pub fn begin_stroke(&mut self) {
+        self.stroke_recorded = false;
+    }
+
+    /// Paints a cell with a tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool` - The `Tool` to paint with.
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// Cells on or outside the walls are left alone. A change is recorded in the undo
+    /// history the first time the current stroke changes something.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut editor = Editor::new(level);
+    /// editor.begin_stroke();
+    /// editor.paint(Tool::Wall, 10, 10, 30, 30);
+    /// assert_eq!(editor.level.layout.obstacles, vec![(10, 10)]);
+    /// editor.undo();
+    /// assert!(editor.level.layout.obstacles.is_empty());
+    /// ```
+    pub fn paint(&mut self, tool: Tool, x: i32, y: i32, width: i32, height: i32) {
+        if x <= 0 || y <= 0 || x >= width - 1 || y >= height - 1 {
+            return;
+        }
+
+        let before = self.level.layout.clone();
+        let mut layout = before.clone();
+        match tool {
+            Tool::Wall => {
+                self.clear(&mut layout, x, y);
+                layout.obstacles.push((x, y));
+            }
+            Tool::Portal => match self.pending_portal {
+                None if layout.portals.len() < MAX_PORTALS => {
+                    self.clear(&mut layout, x, y);
+                    self.pending_portal = Some((x, y));
+                }
+                Some(a) if a != (x, y) => {
+                    self.clear(&mut layout, x, y);
+                    layout.portals.push(Portal { a, b: (x, y) });
+                    self.pending_portal = None;
+                }
+                _ => {}
+            },
+            Tool::Spawn => layout.spawn = (x, y),
+            Tool::Food => {
+                self.clear(&mut layout, x, y);
+                layout.food_spots.push((x, y));
+            }
+            Tool::Erase => self.clear(&mut layout, x, y),
+        }
+
+        if layout != before {
+            if !self.stroke_recorded {
+                self.undo.push(before);
+                self.redo.clear();
+                self.stroke_recorded = true;
+            }
+            self.level.layout = layout;
+            self.cancel_confirmation();
+            self.message = None;
+        }
+    }
+
+    /// Takes back the last change, if any.
+    pub fn undo(&mut self) {
+        self.cancel_confirmation();
+        if let Some(layout) = self.undo.pop() {
+            let current = std::mem::replace(&mut self.level.layout, layout);
+            self.redo.push(current);
+            self.pending_portal = None;
+        }
+    }
+
+    /// Makes the last change taken back by `undo` again, if any.
+    pub fn redo(&mut self) {
+        self.cancel_confirmation();
+        if let Some(layout) = self.redo.pop() {
+            let current = std::mem::replace(&mut self.level.layout, layout);
+            self.undo.push(current);
+            self.pending_portal = None;
+        }
+    }
+
+    /// Checks that the level is playable and writes it to disk, leaving a message
+    /// saying how it went.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// A file of the same name is only replaced by saving a second time, right after
+    /// the message saying that the file exists, or once the level has been saved from
+    /// this editor.
+    pub fn save(&mut self, width: i32, height: i32) {
+        let overwrite = self.confirm_overwrite || self.written;
+        self.cancel_confirmation();
+        if let Err(e) = self.level.validate(width, height) {
+            self.message = Some(format!("not saved: {}", e));
+            return;
+        }
+
+        self.message = Some(match self.level.save(width, height, overwrite) {
+            Ok(name) => {
+                self.saved = self.level.clone();
+                self.written = true;
+                format!("saved {}", name)
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                self.confirm_overwrite = true;
+                format!("{}, s again to replace it", e)
+            }
+            Err(e) => format!("not saved: {}", e),
+        });
+    }
+
+    /// Checks whether the level differs from the one last saved, or opened.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.level.grid != self.saved.grid || self.level.layout != self.saved.layout
+    }
+
+    /// Asks to close the editor.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the editor can be closed. With unsaved changes, the first request only
+    /// leaves a message asking to confirm, and the next one discards the changes, unless
+    /// the level was changed in between.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut editor = Editor::new(level);
+    /// editor.begin_stroke();
+    /// editor.paint(Tool::Wall, 10, 10, 30, 30);
+    /// assert!(!editor.leave());
+    /// assert!(editor.leave());
+    /// ```
+    pub fn leave(&mut self) -> bool {
+        if !self.has_unsaved_changes() || self.confirm_leave {
+            return true;
+        }
+
+        self.cancel_confirmation();
+        self.confirm_leave = true;
+        self.message = Some(String::from("unsaved changes, esc again to discard them"));
+        false
+    }
+
+    /// Forgets a save or a leave that is waiting to be confirmed, along with the message
+    /// asking for it.
+    pub fn cancel_confirmation(&mut self) {
+        if self.confirm_overwrite || self.confirm_leave {
+            self.confirm_overwrite = false;
+            self.confirm_leave = false;
+            self.message = None;
+        }
+    }
+
+    /// Removes everything from a cell except the spawn, which is only ever moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The `Layout` to change.
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    fn clear(&mut self, layout: &mut Layout, x: i32, y: i32) {
+        layout.obstacles.retain(|&cell| cell != (x, y));
+        layout.portals.retain(|portal| portal.exit(x, y).is_none());
+        layout.enemies.retain(|enemy| (enemy.x, enemy.y) != (x, y));
+        layout.food_spots.retain(|&cell| cell != (x, y));
+        if self.pending_portal == Some((x, y)) {
+            self.pending_portal = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Grid;
+
+    fn editor() -> Editor {
+        Editor::new(Level::parse("name=tiny", 30, 30).ok().unwrap())
+    }
+
+    #[test]
+    fn leaving_without_changes_needs_no_confirmation() {
+        let mut editor = editor();
+        assert!(editor.leave());
+
+        editor.begin_stroke();
+        editor.paint(Tool::Wall, 10, 10, 30, 30);
+        editor.undo();
+        assert!(!editor.has_unsaved_changes());
+        assert!(editor.leave());
+    }
+
+    #[test]
+    fn leaving_with_changes_asks_first() {
+        let mut editor = editor();
+        editor.begin_stroke();
+        editor.paint(Tool::Wall, 10, 10, 30, 30);
+        assert!(!editor.leave());
+        assert!(editor.message.is_some());
+        assert!(editor.leave());
+    }
+
+    #[test]
+    fn a_change_cancels_the_confirmation() {
+        let mut editor = editor();
+        editor.level.grid = Grid::Hex;
+        assert!(!editor.leave());
+
+        editor.begin_stroke();
+        editor.paint(Tool::Wall, 10, 10, 30, 30);
+        assert!(editor.message.is_none());
+        assert!(!editor.leave());
+    }
+}
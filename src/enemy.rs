@@ -0,0 +1,255 @@
+use crate::snake::Direction;
+use crate::topology::Topology;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// A chaser only moves on ticks that are a multiple of this, so the snake can outrun it.
+const CHASER_PERIOD: u32 = 2;
+
+/// How an enemy moves.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EnemyKind {
+    /// Walks back and forth in a straight line, turning around at anything in its way.
+    Patrol,
+    /// Moves diagonally and bounces off anything in its way.
+    Ball,
+    /// Follows the shortest free path toward the snake's head, every other tick.
+    Chaser,
+}
+
+/// A cell-sized enemy that moves on its own every tick and kills the snake if their
+/// heads meet.
+///
+/// Enemies move through the same cells as the snake, but never onto walls, obstacles,
+/// portals, food, the snake's body or each other. Only the snake's head is fair game.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Enemy {
+    pub kind: EnemyKind,
+    pub x: i32,
+    pub y: i32,
+    pub direction: Direction,
+}
+
+impl Enemy {
+    /// Works out where the enemy is after the next tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick` - The number of the tick being played.
+    /// * `target` - The cell of the snake's head, which chasers head for.
+    /// * `topology` - The `Topology` of the board.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `is_free` - Whether the enemy may step onto a cell.
+    ///
+    /// # Returns
+    ///
+    /// The moved `Enemy`. An enemy that is boxed in stays where it is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let ball = Enemy { kind: EnemyKind::Ball, x: 1, y: 1, direction: Direction::UpRight };
+    /// let moved = ball.next(1, (9, 9), Grid::Square.topology(), 20, 20, &|(x, y)| x > 0 && y > 0);
+    /// assert_eq!((moved.x, moved.y, moved.direction), (2, 2, Direction::DownRight));
+    /// ```
+    pub fn next(
+        &self,
+        tick: u32,
+        target: (i32, i32),
+        topology: &dyn Topology,
+        width: i32,
+        height: i32,
+        is_free: &dyn Fn((i32, i32)) -> bool,
+    ) -> Enemy {
+        let directions = match self.kind {
+            EnemyKind::Patrol => vec![self.direction, self.direction.opposite()],
+            EnemyKind::Ball => vec![
+                self.direction,
+                mirror_x(self.direction),
+                mirror_y(self.direction),
+                self.direction.opposite(),
+            ],
+            EnemyKind::Chaser if tick % CHASER_PERIOD != 0 => Vec::new(),
+            EnemyKind::Chaser => {
+                let path = self.path_to(target, topology, width, height, is_free);
+                path.into_iter().collect()
+            }
+        };
+
+        for direction in directions {
+            let (x, y) = topology.neighbour(self.x, self.y, direction, width, height);
+            if is_free((x, y)) {
+                return Enemy {
+                    x,
+                    y,
+                    direction,
+                    ..*self
+                };
+            }
+        }
+
+        *self
+    }
+
+    /// Finds the first step of a shortest free path to a cell, with a breadth-first
+    /// search over the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The cell to reach.
+    /// * `topology` - The `Topology` of the board.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `is_free` - Whether a cell may be walked through.
+    ///
+    /// # Returns
+    ///
+    /// The `Direction` of the first step, or `None` if the cell cannot be reached.
+    fn path_to(
+        &self,
+        target: (i32, i32),
+        topology: &dyn Topology,
+        width: i32,
+        height: i32,
+        is_free: &dyn Fn((i32, i32)) -> bool,
+    ) -> Option<Direction> {
+        let index = |(x, y): (i32, i32)| (y * width + x) as usize;
+        let mut first_steps: Vec<Option<Direction>> = vec![None; (width * height) as usize];
+        let mut queue = VecDeque::new();
+
+        for &direction in topology.directions() {
+            let cell = topology.neighbour(self.x, self.y, direction, width, height);
+            if is_free(cell) && first_steps[index(cell)].is_none() {
+                first_steps[index(cell)] = Some(direction);
+                queue.push_back(cell);
+            }
+        }
+
+        while let Some(cell) = queue.pop_front() {
+            let first_step = first_steps[index(cell)];
+            if cell == target {
+                return first_step;
+            }
+
+            for &direction in topology.directions() {
+                let next = topology.neighbour(cell.0, cell.1, direction, width, height);
+                if next != (self.x, self.y) && is_free(next) && first_steps[index(next)].is_none() {
+                    first_steps[index(next)] = first_step;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns a direction with its horizontal part turned around.
+fn mirror_x(direction: Direction) -> Direction {
+    match direction {
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+        Direction::UpLeft => Direction::UpRight,
+        Direction::UpRight => Direction::UpLeft,
+        Direction::DownLeft => Direction::DownRight,
+        Direction::DownRight => Direction::DownLeft,
+        vertical => vertical,
+    }
+}
+
+/// Returns a direction with its vertical part turned around.
+fn mirror_y(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+        Direction::UpLeft => Direction::DownLeft,
+        Direction::UpRight => Direction::DownRight,
+        Direction::DownLeft => Direction::UpLeft,
+        Direction::DownRight => Direction::UpRight,
+        horizontal => horizontal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Grid;
+
+    fn enemy(kind: EnemyKind, x: i32, y: i32, direction: Direction) -> Enemy {
+        Enemy {
+            kind,
+            x,
+            y,
+            direction,
+        }
+    }
+
+    fn inside(cell: (i32, i32)) -> bool {
+        cell.0 > 0 && cell.1 > 0 && cell.0 < 19 && cell.1 < 19
+    }
+
+    fn step(enemy: Enemy, tick: u32, is_free: &dyn Fn((i32, i32)) -> bool) -> Enemy {
+        enemy.next(tick, (9, 9), Grid::Square.topology(), 20, 20, is_free)
+    }
+
+    #[test]
+    fn ball_bounces_off_a_wall() {
+        let ball = enemy(EnemyKind::Ball, 1, 1, Direction::UpRight);
+        let moved = step(ball, 1, &|(x, y)| x > 0 && y > 0);
+        assert!(moved == enemy(EnemyKind::Ball, 2, 2, Direction::DownRight));
+    }
+
+    #[test]
+    fn ball_goes_back_out_of_a_corner() {
+        let ball = enemy(EnemyKind::Ball, 1, 1, Direction::UpLeft);
+        let moved = step(ball, 1, &inside);
+        assert!(moved == enemy(EnemyKind::Ball, 2, 2, Direction::DownRight));
+    }
+
+    #[test]
+    fn ball_moves_straight_through_open_space() {
+        let ball = enemy(EnemyKind::Ball, 5, 5, Direction::DownLeft);
+        let moved = step(ball, 1, &inside);
+        assert!(moved == enemy(EnemyKind::Ball, 4, 6, Direction::DownLeft));
+    }
+
+    #[test]
+    fn patrol_turns_around_at_an_obstacle() {
+        let patrol = enemy(EnemyKind::Patrol, 5, 5, Direction::Right);
+        let moved = step(patrol, 1, &|cell| inside(cell) && cell != (6, 5));
+        assert!(moved == enemy(EnemyKind::Patrol, 4, 5, Direction::Left));
+    }
+
+    #[test]
+    fn boxed_in_enemy_stays_put() {
+        let patrol = enemy(EnemyKind::Patrol, 5, 5, Direction::Right);
+        assert!(step(patrol, 1, &|_| false) == patrol);
+        let ball = enemy(EnemyKind::Ball, 5, 5, Direction::UpRight);
+        assert!(step(ball, 1, &|_| false) == ball);
+    }
+
+    #[test]
+    fn chaser_moves_every_other_tick_around_walls() {
+        // A wall from (9, 1) to (9, 15) stands between the chaser and the target.
+        let is_free = |(x, y): (i32, i32)| inside((x, y)) && !(x == 9 && y <= 15);
+        let chaser = enemy(EnemyKind::Chaser, 5, 5, Direction::Right);
+        let target = (12, 5);
+        let next = |enemy: Enemy, tick| {
+            enemy.next(tick, target, Grid::Square.topology(), 20, 20, &is_free)
+        };
+
+        assert!(next(chaser, 1) == chaser);
+        let moved = next(chaser, 2);
+        assert_eq!((moved.x, moved.y), (5, 6));
+        assert!(moved.direction == Direction::Down);
+    }
+
+    #[test]
+    fn chaser_without_a_path_stays_put() {
+        let is_free = |(x, y): (i32, i32)| inside((x, y)) && x != 9;
+        let chaser = enemy(EnemyKind::Chaser, 5, 5, Direction::Right);
+        let moved = chaser.next(2, (12, 5), Grid::Square.topology(), 20, 20, &is_free);
+        assert!(moved == chaser);
+    }
+}
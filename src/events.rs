@@ -0,0 +1,167 @@
+use crate::death::Death;
+use crate::snake::Direction;
+use std::fmt;
+
+/// Something noteworthy that happened during a game.
+#[derive(Copy, Clone, PartialEq)]
+pub enum GameEvent {
+    /// The snake ate the food at the given cell, bringing the score to `score` and
+    /// the snake to `length` blocks.
+    FoodEaten {
+        x: i32,
+        y: i32,
+        score: u32,
+        length: usize,
+    },
+    /// The player turned the snake.
+    DirectionChanged { from: Direction, to: Direction },
+    /// The snake died with the given final score.
+    Died { score: u32, death: Death },
+    /// The final score entered the high score table at the zero-based `rank`.
+    NewHighScore { rank: usize, score: u32 },
+    /// The snake went hungry and lost a block of its tail, leaving it `length` blocks long.
+    TailLost { length: usize },
+    /// The boosting snake shed the last block of its tail as food at the given cell,
+    /// leaving it `length` blocks long.
+    TailShed { x: i32, y: i32, length: usize },
+    /// The snake ate food it had shed at the given cell, growing back to `length` blocks.
+    DroppedFoodEaten { x: i32, y: i32, length: usize },
+    /// The snake filled every free cell of the board.
+    BoardCleared { score: u32 },
+    /// The snake has been alive for another full minute.
+    Survived { minutes: u32 },
+    /// The goal of the campaign level at the zero-based `level` was reached.
+    LevelCompleted { level: usize },
+    /// The player unlocked an achievement for the first time.
+    AchievementUnlocked { name: &'static str },
+    /// A new game was started.
+    Restarted,
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GameEvent::FoodEaten {
+                x,
+                y,
+                score,
+                length,
+            } => write!(
+                f,
+                "food eaten at ({}, {}), score {}, length {}",
+                x, y, score, length
+            ),
+            GameEvent::DirectionChanged { from, to } => {
+                write!(f, "turned from {} to {}", from.name(), to.name())
+            }
+            GameEvent::Died { score, death } => {
+                write!(f, "died with score {}: {}", score, death)
+            }
+            GameEvent::NewHighScore { rank, score } => {
+                write!(f, "new high score {} at rank {}", score, rank + 1)
+            }
+            GameEvent::TailLost { length } => {
+                write!(f, "lost a block to hunger, length {}", length)
+            }
+            GameEvent::TailShed { x, y, length } => {
+                write!(f, "shed a block at ({}, {}), length {}", x, y, length)
+            }
+            GameEvent::DroppedFoodEaten { x, y, length } => {
+                write!(f, "ate dropped food at ({}, {}), length {}", x, y, length)
+            }
+            GameEvent::BoardCleared { score } => {
+                write!(f, "board cleared with score {}", score)
+            }
+            GameEvent::Survived { minutes } => write!(f, "alive for {} minutes", minutes),
+            GameEvent::LevelCompleted { level } => {
+                write!(f, "campaign level {} completed", level + 1)
+            }
+            GameEvent::AchievementUnlocked { name } => {
+                write!(f, "achievement unlocked: {}", name)
+            }
+            GameEvent::Restarted => write!(f, "restarted"),
+        }
+    }
+}
+
+/// A subscriber's callback.
+pub type EventHandler = Box<dyn FnMut(&GameEvent)>;
+
+/// Identifies a subscription so that it can be cancelled later.
+#[derive(Copy, Clone, PartialEq)]
+pub struct SubscriptionId(usize);
+
+/// Delivers game events to every interested subscriber.
+///
+/// Subscribers are called synchronously, in the order they subscribed, as soon as
+/// an event is published. Each event carries the data subscribers need, so they
+/// never have to look into the game itself.
+pub struct EventBus {
+    next_id: usize,
+    subscribers: Vec<(SubscriptionId, EventHandler)>,
+}
+
+impl EventBus {
+    /// Creates an event bus without subscribers.
+    pub fn new() -> EventBus {
+        EventBus {
+            next_id: 0,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Registers a handler that is called for every published event.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The closure to call with each event.
+    ///
+    /// # Returns
+    ///
+    /// A `SubscriptionId` that can be passed to `unsubscribe`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut bus = EventBus::new();
+    /// let id = bus.subscribe(|event| println!("{}", event));
+    /// bus.publish(GameEvent::Restarted);
+    /// bus.unsubscribe(id);
+    /// ```
+    pub fn subscribe<F>(&mut self, handler: F) -> SubscriptionId
+    where
+        F: FnMut(&GameEvent) + 'static,
+    {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.push((id, Box::new(handler)));
+        id
+    }
+
+    /// Removes a handler registered with `subscribe`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The `SubscriptionId` returned by `subscribe`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the subscription existed and was removed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let count = self.subscribers.len();
+        self.subscribers
+            .retain(|(subscription, _)| *subscription != id);
+        self.subscribers.len() != count
+    }
+
+    /// Sends an event to all subscribers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `GameEvent` to deliver.
+    pub fn publish(&mut self, event: GameEvent) {
+        for (_, handler) in &mut self.subscribers {
+            handler(&event);
+        }
+    }
+}
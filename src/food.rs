@@ -0,0 +1,64 @@
+use crate::theme::Theme;
+use piston_window::types::Color;
+use serde::{Deserialize, Serialize};
+
+/// The kinds of food a snake can eat. Every kind makes the snake one block longer, but
+/// some are worth more points than others.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FoodKind {
+    /// The regular food, worth one point.
+    Normal,
+    /// A rarer treat worth three points.
+    Bonus,
+}
+
+impl FoodKind {
+    /// All kinds of food, in the order they are listed in level files.
+    pub const ALL: [FoodKind; 2] = [FoodKind::Normal, FoodKind::Bonus];
+
+    /// Returns the name of the kind as written in level files.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            FoodKind::Normal => "normal",
+            FoodKind::Bonus => "bonus",
+        }
+    }
+
+    /// Finds a kind of food by its name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the kind, as returned by `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// assert!(FoodKind::from_name("BONUS") == Some(FoodKind::Bonus));
+    /// assert!(FoodKind::from_name("cake").is_none());
+    /// ```
+    pub fn from_name(name: &str) -> Option<FoodKind> {
+        FoodKind::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns how many points eating the food is worth.
+    pub fn points(&self) -> u32 {
+        match *self {
+            FoodKind::Normal => 1,
+            FoodKind::Bonus => 3,
+        }
+    }
+
+    /// Returns the color the food is drawn with in a theme.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The `Theme` to draw with.
+    pub fn color(&self, theme: &Theme) -> Color {
+        match *self {
+            FoodKind::Normal => theme.food,
+            FoodKind::Bonus => theme.highlight,
+        }
+    }
+}
@@ -1,33 +1,86 @@
-use crate::draw::{draw_block, draw_rectangle};
-use crate::snake::{Direction, Snake};
-use piston_window::types::Color;
+use crate::achievements::{Achievement, AchievementTracker, ACHIEVEMENTS};
+use crate::campaign::{Campaign, CampaignLevel};
+use crate::daily::{DailyChallenge, DailyLeaderboard, DailyResult, DAILY_SIZE};
+use crate::death::Death;
+use crate::draw::{draw_bar, draw_rectangle, draw_text, draw_text_centered};
+use crate::editor::{Editor, Tool};
+use crate::events::{EventBus, GameEvent};
+use crate::highscores::HighScores;
+use crate::level::{load_levels, Layout, Level};
+use crate::menu::{draw_hint, draw_menu};
+use crate::mode::Rules;
+use crate::profile::{Profile, RunSummary, RECENT_SCORES};
+use crate::replay::{Recording, Replay};
+use crate::rewind::{RewindBuffer, REWIND_SECONDS};
+use crate::save::{SavedGame, SAVE_VERSION};
+use crate::settings::{Settings, Speed};
+use crate::snake::{Direction, Skin};
+use crate::sprite::SnakeSprites;
+use crate::state::{move_selection, GameState, PlayMode, SettingsItem, TitleItem};
+use crate::theme::{load_themes, Theme, PORTAL_COLORS};
+use crate::timestep::FixedTimestep;
+use crate::topology::{Grid, Topology};
+use crate::world::{World, HUNGER_TICKS};
 use piston_window::*;
-use rand::{thread_rng, Rng};
+use std::collections::VecDeque;
 
-const FOOD_COLOR: Color = [0.80, 0.00, 0.00, 1.0];
-const BORDER_COLOR: Color = [0.00, 0.00, 0.00, 1.0];
-const GAMEOVER_COLOR: Color = [0.90, 0.00, 0.00, 0.5];
-
-const MOVING_PERIOD: f64 = 0.1;
-const RESTART_TIME: f64 = 1.0;
+/// How long an achievement popup stays on screen, in seconds.
+const POPUP_DURATION: f64 = 3.0;
 
 /// Represents the game state for the Snake game.
 ///
-/// The `Game` struct holds the state of the game including the snake,
-/// the presence and position of the food, the dimensions of the game area,
-/// the game over status, and the waiting time for game updates.
+/// The `Game` struct holds the `World` being played, the current screen and kind of
+/// game, and everything the player keeps between games, and it turns input and elapsed
+/// time into ticks of the world and events for observers.
 pub struct Game {
-    snake: Snake,
-
-    food_exists: bool,
-    food_x: i32,
-    food_y: i32,
+    world: World,
 
     width: i32,
     height: i32,
+    /// The size the game was created with, which campaign levels of their own size
+    /// replace while they are played.
+    default_size: (i32, i32),
 
-    game_over: bool,
-    waiting_time: f64,
+    state: GameState,
+    mode: PlayMode,
+    /// The game mode chosen on the title screen, kept for the next regular game.
+    rules: Rules,
+    /// The rules of the day, while playing the daily challenge.
+    daily: Option<DailyChallenge>,
+    /// The index of the level being played, if any.
+    level: Option<usize>,
+    /// The fixed timestep that paces the moves of the snake.
+    clock: FixedTimestep,
+    time_alive: f64,
+    recording: Recording,
+    death: Option<Death>,
+    /// The last few seconds of play in practice mode, to rewind after dying.
+    history: RewindBuffer,
+
+    settings: Settings,
+    themes: Vec<Theme>,
+    theme_index: usize,
+    levels: Vec<Level>,
+    /// The campaign levels and the player's progress through them.
+    campaign: Campaign,
+    sprites: Option<SnakeSprites>,
+    high_scores: HighScores,
+    high_score_rank: Option<usize>,
+    daily_scores: DailyLeaderboard,
+    daily_rank: Option<usize>,
+    profile: Profile,
+    achievements: AchievementTracker,
+    popups: VecDeque<&'static Achievement>,
+    popup_time: f64,
+    /// The level being edited, in the level editor.
+    editor: Option<Editor>,
+    /// Where the mouse is on the board, in blocks.
+    cursor: Option<[f64; 2]>,
+    mouse_held: Option<MouseButton>,
+    /// The cell the snake was last sent toward with the mouse, while playing.
+    target: Option<(i32, i32)>,
+    quit_requested: bool,
+    events: EventBus,
 }
 
 impl Game {
@@ -48,33 +101,90 @@ impl Game {
     /// let game = Game::new(20, 20);
     /// assert_eq!(game.width, 20);
     /// assert_eq!(game.height, 20);
-    /// assert!(game.food_exists);
+    /// assert!(game.world.food_exists);
     /// ```
     ///
     /// This function initializes a new `Game` instance with a snake starting
-    /// at position (2, 2), food at position (6, 4), and the game not being over.
+    /// at position (2, 2) and food at position (6, 4). The game starts on the title
+    /// screen, with the settings, high scores, themes, levels and campaign progress loaded
+    /// from the data directory.
     pub fn new(width: i32, height: i32) -> Game {
+        let settings = Settings::load();
+        let themes = load_themes();
+        let theme_index = themes
+            .iter()
+            .position(|theme| theme.name == settings.theme)
+            .unwrap_or(0);
+
         Game {
-            snake: Snake::new(2, 2),
-            waiting_time: 0.0,
-            food_exists: true,
-            food_x: 6,
-            food_y: 4,
+            world: World::new(
+                width,
+                height,
+                Grid::Square,
+                rand::random(),
+                Layout::default(),
+            ),
+            clock: FixedTimestep::default(),
+            time_alive: 0.0,
             width,
             height,
-            game_over: false,
+            default_size: (width, height),
+            state: GameState::Title { selected: 0 },
+            mode: PlayMode::Normal,
+            rules: Rules::default(),
+            daily: None,
+            level: None,
+            recording: Recording::default(),
+            death: None,
+            history: RewindBuffer::new(REWIND_SECONDS, settings.speed.moving_period()),
+            settings,
+            themes,
+            theme_index,
+            levels: load_levels(width, height),
+            campaign: Campaign::load(),
+            sprites: None,
+            high_scores: HighScores::load(),
+            high_score_rank: None,
+            daily_scores: DailyLeaderboard::load(),
+            daily_rank: None,
+            profile: Profile::load(),
+            achievements: AchievementTracker::new(),
+            popups: VecDeque::new(),
+            popup_time: 0.0,
+            editor: None,
+            cursor: None,
+            mouse_held: None,
+            target: None,
+            quit_requested: false,
+            events: EventBus::new(),
         }
     }
 
-    /// Handles key press events to control the snake.
+    /// Handles key press events according to the current screen.
     ///
     /// # Arguments
     ///
     /// * `key` - A `Key` representing the key that was pressed.
     ///
-    /// If the game is over, this function does nothing.
-    /// Otherwise, it checks the key pressed and sets the direction of the snake accordingly.
-    /// If the new direction is directly opposite to the current direction of the snake, it ignores the input.
+    /// `M` toggles muting on every screen. Each screen of the game reacts to its own keys:
+    /// - Title, levels, campaign, settings: `Up`/`Down` select an entry, `Return`
+    ///   activates it. On the play entry of the title screen, `Left`/`Right` pick the
+    ///   game mode.
+    /// - Playing: the arrow keys steer the snake, holding `Shift` boosts it, `P`, `Space`
+    ///   or `Escape` pause.
+    /// - Paused: `P`, `Space` or `Return` resume, `S` saves the game and goes back to the
+    ///   title screen, `Escape` goes back to the title screen.
+    /// - Game over: `R` or `Return` start a new game, `Escape` goes back to the title screen.
+    /// - Level complete: `Return` starts the next campaign level, `Escape` goes back to
+    ///   the campaign screen.
+    /// - Rewinding: `Left` and `Right` step back and forth through the last seconds of
+    ///   play, `Return` resumes from the shown moment, `R` starts over and `Escape` goes
+    ///   back to the title screen.
+    /// - High scores, statistics: any key goes back to the title screen.
+    /// - Levels: `E` opens the selected level in the editor.
+    /// - Editor: `1` to `5` or `Tab` pick a tool, `G` changes the board, `Z` undoes, `Y`
+    ///   redoes, `S` saves the level and `Escape` goes back to the levels. Replacing a
+    ///   level file and leaving with unsaved changes take a second press to confirm.
     ///
     /// # Example
     ///
@@ -82,220 +192,1766 @@ impl Game {
     /// use piston_window::Key;
     ///
     /// let mut game = Game::new(20, 20);
+    /// game.key_pressed(Key::Return);
     /// game.key_pressed(Key::Up);
-    /// assert_eq!(game.snake.head_direction(), Direction::Up);
+    /// assert_eq!(game.world.snake.head_direction(), Direction::Up);
     /// ```
     pub fn key_pressed(&mut self, key: Key) {
-        if self.game_over {
+        if key == Key::M {
+            self.settings.muted = !self.settings.muted;
+            self.save_settings();
             return;
         }
 
-        let dir = match key {
-            Key::Up => Some(Direction::Up),
-            Key::Down => Some(Direction::Down),
-            Key::Left => Some(Direction::Left),
-            Key::Right => Some(Direction::Right),
-            _ => Some(self.snake.head_direction()),
-        };
-
-        if let Some(dir) = dir {
-            if dir == self.snake.head_direction().opposite() {
-                return;
+        match self.state {
+            GameState::Title { selected } => self.title_key_pressed(key, selected),
+            GameState::Levels { selected } => self.levels_key_pressed(key, selected),
+            GameState::Campaign { selected } => self.campaign_key_pressed(key, selected),
+            GameState::Playing => self.playing_key_pressed(key),
+            GameState::Paused => match key {
+                Key::P | Key::Space | Key::Return => self.state = GameState::Playing,
+                Key::S => self.suspend_game(),
+                Key::Escape => self.state = GameState::Title { selected: 0 },
+                _ => {}
+            },
+            GameState::GameOver => match key {
+                Key::R | Key::Return => self.restart(),
+                Key::Escape => self.state = GameState::Title { selected: 0 },
+                _ => {}
+            },
+            GameState::LevelComplete => self.level_complete_key_pressed(key),
+            GameState::Rewinding { steps_back } => self.rewinding_key_pressed(key, steps_back),
+            GameState::Settings { selected } => self.settings_key_pressed(key, selected),
+            GameState::HighScores => {
+                let selected = TitleItem::HighScores.index();
+                self.state = GameState::Title { selected };
             }
+            GameState::Stats => {
+                let selected = TitleItem::Stats.index();
+                self.state = GameState::Title { selected };
+            }
+            GameState::Editor => self.editor_key_pressed(key),
         }
+    }
 
-        self.update_snake(dir);
+    /// Handles the mouse moving over the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the cursor on the board, in blocks, as given by
+    ///   `board_position`.
+    ///
+    /// In the level editor, dragging with a button held keeps painting. While playing,
+    /// dragging with a button held steers the snake toward the cursor.
+    pub fn mouse_moved(&mut self, position: [f64; 2]) {
+        self.cursor = Some(position);
+        if let Some(button) = self.mouse_held {
+            self.paint_at_cursor(button, false);
+            self.aim_at_cursor();
+        }
     }
 
-    /// Draws the game state on the screen.
+    /// Handles a key release.
     ///
     /// # Arguments
     ///
-    /// * `con` - A reference to the `Context` for rendering.
-    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    /// * `key` - A `Key` representing the key that was released.
     ///
-    /// This function draws the snake, food (if it exists), borders, and a game over screen
-    /// if the game is over.
+    /// Releasing `Shift` while playing or paused stops the snake boosting.
     ///
     /// # Example
     ///
     /// ```rust
-    /// // Assuming you have a valid Context and G2d instance:
-    /// // game.draw(&con, &mut g);
+    /// use piston_window::Key;
+    ///
+    /// let mut game = Game::new(20, 20);
+    /// game.key_released(Key::LShift);
+    /// assert!(!game.world.snake.is_boosting());
     /// ```
-    pub fn draw(&self, con: &Context, g: &mut G2d) {
-        self.snake.draw(con, g);
-
-        if self.food_exists {
-            draw_block(FOOD_COLOR, self.food_x, self.food_y, con, g);
+    pub fn key_released(&mut self, key: Key) {
+        let playing = matches!(self.state, GameState::Playing | GameState::Paused);
+        if playing && matches!(key, Key::LShift | Key::RShift) {
+            self.boost(false);
         }
+    }
 
-        draw_rectangle(BORDER_COLOR, 0, 0, self.width, 1, con, g);
-        draw_rectangle(BORDER_COLOR, 0, self.height - 1, self.width, 1, con, g);
-        draw_rectangle(BORDER_COLOR, 0, 0, 1, self.height, con, g);
-        draw_rectangle(BORDER_COLOR, self.width - 1, 0, 1, self.height, con, g);
-
-        if self.game_over {
-            draw_rectangle(GAMEOVER_COLOR, 0, 0, self.width, self.height, con, g);
+    /// Handles a mouse button being pressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `button` - The `MouseButton` that was pressed.
+    ///
+    /// In the level editor, the left button paints with the current tool and the right
+    /// button erases. While playing, any button sends the snake toward the cell under
+    /// the cursor, which is how the game is played with a mouse or on a touchscreen.
+    pub fn mouse_pressed(&mut self, button: MouseButton) {
+        self.mouse_held = Some(button);
+        if let Some(editor) = &mut self.editor {
+            editor.begin_stroke();
         }
+        self.paint_at_cursor(button, true);
+        self.aim_at_cursor();
     }
 
-    /// Updates the game state based on the elapsed time.
+    /// Handles a mouse button being released.
     ///
     /// # Arguments
     ///
-    /// * `delta_time` - A floating-point number representing the time elapsed since the last update.
+    /// * `button` - The `MouseButton` that was released.
+    pub fn mouse_released(&mut self, button: MouseButton) {
+        if self.mouse_held == Some(button) {
+            self.mouse_held = None;
+        }
+    }
+
+    /// Returns the size of the board being shown, in blocks.
+    ///
+    /// # Returns
+    ///
+    /// The width and height of the board, walls included. This is the size of the board
+    /// of the current game, or the size the game was created with in the level editor.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let game = Game::new(30, 30);
+    /// assert_eq!(game.board_size(), (30, 30));
+    /// ```
+    pub fn board_size(&self) -> (i32, i32) {
+        match self.state {
+            GameState::Editor => self.default_size,
+            _ => (self.width, self.height),
+        }
+    }
+
+    /// Returns the color theme chosen in the settings.
+    pub fn theme(&self) -> &Theme {
+        &self.themes[self.theme_index]
+    }
+
+    /// Returns the player's current settings.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Returns the bus on which the game publishes its events.
+    ///
+    /// # Returns
     ///
-    /// This function updates the waiting time and performs several actions based on the game state:
-    /// - If the game is over and the waiting time exceeds `RESTART_TIME`, the game is restarted.
-    /// - If food does not exist, new food is added to the game.
-    /// - If the waiting time exceeds `MOVING_PERIOD`, the snake is updated.
+    /// A mutable reference to the `EventBus`, to subscribe or unsubscribe observers.
     ///
     /// # Example
     ///
     /// ```rust
     /// let mut game = Game::new(20, 20);
-    /// game.update(0.1);
+    /// game.events_mut().subscribe(|event| println!("{}", event));
     /// ```
-    pub fn update(&mut self, delta_time: f64) {
-        self.waiting_time += delta_time;
+    pub fn events_mut(&mut self) -> &mut EventBus {
+        &mut self.events
+    }
+
+    /// Sets the sprite sheet used when the snake is drawn with sprites.
+    ///
+    /// # Arguments
+    ///
+    /// * `sprites` - The loaded sprite sheet, or `None` to draw plain blocks instead.
+    pub fn set_sprites(&mut self, sprites: Option<SnakeSprites>) {
+        self.sprites = sprites;
+    }
+
+    /// Returns `true` once the player has chosen to quit from the title screen.
+    pub fn quit_requested(&self) -> bool {
+        self.quit_requested
+    }
+
+    /// Handles a key press on the title screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A `Key` representing the key that was pressed.
+    /// * `selected` - The index of the currently selected menu entry.
+    fn title_key_pressed(&mut self, key: Key, selected: usize) {
+        let count = TitleItem::ALL.len();
+        let item = TitleItem::ALL[selected];
+        match key {
+            Key::Up => {
+                let selected = move_selection(selected, count, false);
+                self.state = GameState::Title { selected };
+            }
+            Key::Down => {
+                let selected = move_selection(selected, count, true);
+                self.state = GameState::Title { selected };
+            }
+            Key::Left if item == TitleItem::Play => {
+                self.rules = self.rules.previous(self.settings.speed);
+            }
+            Key::Right if item == TitleItem::Play => {
+                self.rules = self.rules.next(self.settings.speed);
+            }
+            Key::Return => match item {
+                TitleItem::Play => self.start(PlayMode::Normal),
+                TitleItem::Daily => {
+                    let day = DailyChallenge::today(self.width, self.height).day;
+                    self.start(PlayMode::Daily { day });
+                }
+                TitleItem::Campaign => {
+                    let selected = (0..self.campaign.levels.len())
+                        .rfind(|&index| self.campaign.is_unlocked(index))
+                        .unwrap_or(0);
+                    self.state = GameState::Campaign { selected };
+                }
+                TitleItem::Levels => self.state = GameState::Levels { selected: 0 },
+                TitleItem::Resume => self.resume_game(),
+                TitleItem::Practice => self.start(PlayMode::Practice),
+                TitleItem::Settings => self.state = GameState::Settings { selected: 0 },
+                TitleItem::Stats => self.state = GameState::Stats,
+                TitleItem::HighScores => self.state = GameState::HighScores,
+                TitleItem::Quit => self.quit_requested = true,
+            },
+            Key::Escape => self.quit_requested = true,
+            _ => {}
+        }
+    }
 
-        if self.game_over {
-            if self.waiting_time > RESTART_TIME {
-                self.restart();
+    /// Handles a key press while the snake is moving.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A `Key` representing the key that was pressed.
+    ///
+    /// This function checks the key pressed and sets the direction of the snake accordingly.
+    /// The arrow keys and the diagonal keys Q, E, Z and C are turned into a direction of the
+    /// board by its `Topology`; any other key keeps the current direction.
+    /// If the new direction is directly opposite to the current direction of the snake, it ignores the input.
+    /// The pause keys switch to the paused screen instead of moving the snake, and the
+    /// `Shift` keys start boosting it, until they are released. Steering with the keys
+    /// stops the snake heading for a cell picked with the mouse.
+    fn playing_key_pressed(&mut self, key: Key) {
+        let current = self.world.snake.head_direction();
+        let pressed = match key {
+            Key::Up => Direction::Up,
+            Key::Down => Direction::Down,
+            Key::Left => Direction::Left,
+            Key::Right => Direction::Right,
+            Key::Q => Direction::UpLeft,
+            Key::E => Direction::UpRight,
+            Key::Z => Direction::DownLeft,
+            Key::C => Direction::DownRight,
+            Key::P | Key::Space | Key::Escape => {
+                self.state = GameState::Paused;
+                return;
+            }
+            Key::LShift | Key::RShift => {
+                self.boost(true);
+                return;
             }
+            _ => current,
+        };
+
+        let dir = self
+            .world
+            .topology()
+            .steer(current, pressed)
+            .unwrap_or(current);
+        if dir == current.opposite() {
             return;
         }
 
-        if !self.food_exists {
-            self.add_food();
+        self.target = None;
+        self.update_snake(Some(dir));
+        self.clock.reset();
+    }
+
+    /// Starts or stops boosting the snake, recording the change if there was one.
+    ///
+    /// # Arguments
+    ///
+    /// * `boosting` - `true` to start boosting, `false` to stop.
+    fn boost(&mut self, boosting: bool) {
+        if self.world.set_boosting(boosting) {
+            self.recording.record_boost(&self.world);
         }
+    }
 
-        if self.waiting_time > MOVING_PERIOD {
-            self.update_snake(None);
+    /// Handles a key press while rewinding after dying in practice mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A `Key` representing the key that was pressed.
+    /// * `steps_back` - How many ticks back the currently shown snapshot is.
+    ///
+    /// Stepping restores the snapshot right away, so the board shows the game as it
+    /// was at that moment. Resuming drops the newer snapshots and pauses the game, so
+    /// the player can get ready before the snake moves again. It goes on from the shown
+    /// snapshot, which right after dying is the world before the fatal tick rather than
+    /// the one the fatal tick left behind.
+    fn rewinding_key_pressed(&mut self, key: Key, steps_back: usize) {
+        match key {
+            Key::Left if steps_back + 1 < self.history.len() => {
+                self.show_snapshot(steps_back + 1);
+            }
+            Key::Right if steps_back > 0 => self.show_snapshot(steps_back - 1),
+            Key::Return | Key::Space => {
+                if let Some(snapshot) = self.history.get(steps_back) {
+                    self.world = snapshot.clone();
+                }
+                self.history.discard_newest(steps_back);
+                self.death = None;
+                self.boost(false);
+                self.state = GameState::Paused;
+            }
+            Key::R => self.restart(),
+            Key::Escape => {
+                let selected = TitleItem::Practice.index();
+                self.state = GameState::Title { selected };
+            }
+            _ => {}
         }
     }
 
-    /// Checks if the snake's head is at the position of the food.
+    /// Handles a key press on the level selection screen.
     ///
-    /// If the snake's head is at the same position as the food, this function:
-    /// - Sets `food_exists` to `false`.
-    /// - Calls `restore_tail` on the snake to make it grow.
+    /// # Arguments
     ///
-    /// # Example
+    /// * `key` - A `Key` representing the key that was pressed.
+    /// * `selected` - The index of the currently selected level.
     ///
-    /// ```rust
-    /// let mut game = Game::new(20, 20);
-    /// game.check_eating();
-    /// ```
-    fn check_eating(&mut self) {
-        let (head_x, head_y): (i32, i32) = self.snake.head_position();
-        if self.food_exists && self.food_x == head_x && self.food_y == head_y {
-            self.food_exists = false;
-            self.snake.restore_tail();
+    /// `Return` starts a game on the selected level, `Escape` goes back to the title
+    /// screen.
+    fn levels_key_pressed(&mut self, key: Key, selected: usize) {
+        let count = self.levels.len() + 1;
+        match key {
+            Key::Up => {
+                let selected = move_selection(selected, count, false);
+                self.state = GameState::Levels { selected };
+            }
+            Key::Down => {
+                let selected = move_selection(selected, count, true);
+                self.state = GameState::Levels { selected };
+            }
+            Key::Return if selected < self.levels.len() => self.start_level(selected),
+            Key::Return => {
+                let level = Level {
+                    name: format!("LEVEL {}", self.levels.len() + 1),
+                    grid: self.settings.grid,
+                    layout: Layout::default(),
+                };
+                self.open_editor(level);
+            }
+            Key::E if selected < self.levels.len() => {
+                self.open_editor(self.levels[selected].clone())
+            }
+            Key::Escape => {
+                let selected = TitleItem::Levels.index();
+                self.state = GameState::Title { selected };
+            }
+            _ => {}
         }
     }
 
-    /// Checks if the snake is alive based on its next head position.
+    /// Handles a key press on the campaign screen.
     ///
     /// # Arguments
     ///
-    /// * `dir` - An optional `Direction` indicating the direction in which the snake will move next.
+    /// * `key` - A `Key` representing the key that was pressed.
+    /// * `selected` - The index of the currently selected campaign level.
     ///
-    /// # Returns
+    /// `Return` starts the selected level if it is unlocked, `Escape` goes back to the
+    /// title screen.
+    fn campaign_key_pressed(&mut self, key: Key, selected: usize) {
+        let count = self.campaign.levels.len();
+        match key {
+            Key::Up if count > 0 => {
+                let selected = move_selection(selected, count, false);
+                self.state = GameState::Campaign { selected };
+            }
+            Key::Down if count > 0 => {
+                let selected = move_selection(selected, count, true);
+                self.state = GameState::Campaign { selected };
+            }
+            Key::Return if selected < count && self.campaign.is_unlocked(selected) => {
+                self.start(PlayMode::Campaign { level: selected });
+            }
+            Key::Escape => {
+                let selected = TitleItem::Campaign.index();
+                self.state = GameState::Title { selected };
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles a key press after reaching the goal of a campaign level.
     ///
-    /// A boolean indicating whether the snake is alive. The snake is considered alive if:
-    /// - Its next head position does not overlap with its tail.
-    /// - Its next head position is within the boundaries of the game area.
+    /// # Arguments
     ///
-    /// # Example
+    /// * `key` - A `Key` representing the key that was pressed.
     ///
-    /// ```rust
-    /// let game = Game::new(20, 20);
-    /// let is_alive = game.check_if_snake_alive(Some(Direction::Up));
-    /// assert!(is_alive);
-    /// ```
-    fn check_if_snake_alive(&self, dir: Option<Direction>) -> bool {
-        let (next_x, next_y) = self.snake.next_head(dir);
+    /// `Return` starts the next level, or goes back to the campaign screen after the
+    /// last one. `Escape` goes back to the campaign screen.
+    fn level_complete_key_pressed(&mut self, key: Key) {
+        let PlayMode::Campaign { level } = self.mode else {
+            return;
+        };
+
+        let next = level + 1;
+        match key {
+            Key::Return if next < self.campaign.levels.len() => {
+                self.start(PlayMode::Campaign { level: next });
+            }
+            Key::Return | Key::Escape => {
+                let selected = next.min(self.campaign.levels.len() - 1);
+                self.state = GameState::Campaign { selected };
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the level editor.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The `Level` to edit.
+    fn open_editor(&mut self, level: Level) {
+        self.editor = Some(Editor::new(level));
+        self.state = GameState::Editor;
+    }
+
+    /// Handles a key press in the level editor.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A `Key` representing the key that was pressed.
+    fn editor_key_pressed(&mut self, key: Key) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+
+        let number_keys = [Key::D1, Key::D2, Key::D3, Key::D4, Key::D5];
+        if let Some(index) = number_keys.iter().position(|&k| k == key) {
+            editor.tool = Tool::ALL[index];
+            return;
+        }
 
-        if self.snake.overlap_tail(next_x, next_y) {
-            return false;
+        match key {
+            Key::Tab => {
+                let index = Tool::ALL
+                    .iter()
+                    .position(|&tool| tool == editor.tool)
+                    .unwrap();
+                editor.tool = Tool::ALL[(index + 1) % Tool::ALL.len()];
+            }
+            Key::G => {
+                editor.cancel_confirmation();
+                editor.level.grid = editor.level.grid.next();
+            }
+            Key::Z => editor.undo(),
+            Key::Y => editor.redo(),
+            Key::S => {
+                let (width, height) = self.default_size;
+                editor.save(width, height);
+                self.levels = load_levels(width, height);
+            }
+            Key::Escape if editor.leave() => {
+                self.editor = None;
+                self.state = GameState::Levels { selected: 0 };
+            }
+            _ => {}
         }
+    }
 
-        next_x > 0 && next_y > 0 && next_x < self.width - 1 && next_y < self.height - 1
+    /// Sends the snake toward the cell under the mouse cursor, while playing.
+    ///
+    /// The snake turns toward the cell on the following ticks, see `steer_to_target`.
+    fn aim_at_cursor(&mut self) {
+        let Some([x, y]) = self.cursor else {
+            return;
+        };
+        if self.state == GameState::Playing {
+            self.target = Some(self.world.topology().cell_at(x, y));
+        }
     }
 
-    /// Adds food to the game at a random position that does not overlap with the snake's tail.
+    /// Returns the direction that takes the snake closer to the cell it was sent toward
+    /// with the mouse.
+    ///
+    /// # Returns
     ///
-    /// This function generates random coordinates within the game area and ensures that the food
-    /// does not overlap with the snake's tail. Once a valid position is found, it sets the `food_x`
-    /// and `food_y` coordinates and marks `food_exists` as `true`.
+    /// The direction chosen by `steer_toward`, or `None` if the snake is already heading
+    /// the best way. Once its head is on the target, the target is dropped and the snake
+    /// goes straight on.
     ///
     /// # Example
     ///
     /// ```rust
     /// let mut game = Game::new(20, 20);
-    /// game.add_food();
-    /// assert!(game.food_exists);
+    /// game.key_pressed(Key::Return);
+    /// game.target = Some((2, 10));
+    /// assert_eq!(game.steer_to_target(), Some(Direction::Down));
     /// ```
-    fn add_food(&mut self) {
-        let mut rng = thread_rng();
+    fn steer_to_target(&mut self) -> Option<Direction> {
+        let (target_x, target_y) = self.target?;
+        let (head_x, head_y) = self.world.snake.head_position();
+        if (head_x, head_y) == (target_x, target_y) {
+            self.target = None;
+            return None;
+        }
+
+        steer_toward(
+            self.world.topology(),
+            (head_x, head_y),
+            self.world.snake.head_direction(),
+            (target_x, target_y),
+        )
+    }
 
-        let mut new_x = rng.gen_range(1..self.width - 1);
-        let mut new_y = rng.gen_range(1..self.height - 1);
-        while self.snake.overlap_tail(new_x, new_y) {
-            new_x = rng.gen_range(1..self.width - 1);
-            new_y = rng.gen_range(1..self.height - 1);
+    /// Paints the cell under the mouse cursor in the level editor.
+    ///
+    /// # Arguments
+    ///
+    /// * `button` - The `MouseButton` held down; the right button erases.
+    /// * `pressed` - Whether the button was just pressed, rather than dragged. Tools that
+    ///   do not paint while dragging only paint when it was just pressed.
+    fn paint_at_cursor(&mut self, button: MouseButton, pressed: bool) {
+        let (Some(editor), Some([x, y])) = (&mut self.editor, self.cursor) else {
+            return;
+        };
+        if self.state != GameState::Editor {
+            return;
         }
 
-        self.food_x = new_x;
-        self.food_y = new_y;
-        self.food_exists = true;
+        let tool = match button {
+            MouseButton::Right => Tool::Erase,
+            _ => editor.tool,
+        };
+        if pressed || tool.paints_while_dragging() {
+            let (cell_x, cell_y) = editor.level.grid.topology().cell_at(x, y);
+            let (width, height) = self.default_size;
+            editor.paint(tool, cell_x, cell_y, width, height);
+        }
     }
 
-    /// Updates the snake's position and checks for game over conditions.
+    /// Handles a key press on the settings screen.
     ///
     /// # Arguments
     ///
-    /// * `dir` - An optional `Direction` indicating the direction in which the snake will move next.
+    /// * `key` - A `Key` representing the key that was pressed.
+    /// * `selected` - The index of the currently selected menu entry.
+    ///
+    /// `Left` and `Right` change the selected value, `Return` cycles it forward.
+    /// The settings are saved when leaving the screen.
+    fn settings_key_pressed(&mut self, key: Key, selected: usize) {
+        let count = SettingsItem::ALL.len();
+        let item = SettingsItem::ALL[selected];
+        match key {
+            Key::Up => {
+                let selected = move_selection(selected, count, false);
+                self.state = GameState::Settings { selected };
+            }
+            Key::Down => {
+                let selected = move_selection(selected, count, true);
+                self.state = GameState::Settings { selected };
+            }
+            Key::Left if item == SettingsItem::Speed => {
+                self.settings.speed = self.settings.speed.previous();
+            }
+            Key::Right | Key::Return if item == SettingsItem::Speed => {
+                self.settings.speed = self.settings.speed.next();
+            }
+            Key::Left if item == SettingsItem::Theme => {
+                let count = self.themes.len();
+                self.select_theme(move_selection(self.theme_index, count, false));
+            }
+            Key::Right | Key::Return if item == SettingsItem::Theme => {
+                let count = self.themes.len();
+                self.select_theme(move_selection(self.theme_index, count, true));
+            }
+            Key::Left if item == SettingsItem::SnakeStyle => {
+                self.settings.snake_style = self.settings.snake_style.previous();
+            }
+            Key::Right | Key::Return if item == SettingsItem::SnakeStyle => {
+                self.settings.snake_style = self.settings.snake_style.next();
+            }
+            Key::Left if item == SettingsItem::EffectsVolume => {
+                self.settings.effects_volume = self.settings.effects_volume.down();
+            }
+            Key::Right if item == SettingsItem::EffectsVolume => {
+                self.settings.effects_volume = self.settings.effects_volume.up();
+            }
+            Key::Left if item == SettingsItem::MusicVolume => {
+                self.settings.music_volume = self.settings.music_volume.down();
+            }
+            Key::Right if item == SettingsItem::MusicVolume => {
+                self.settings.music_volume = self.settings.music_volume.up();
+            }
+            Key::Left | Key::Right | Key::Return if item == SettingsItem::Mute => {
+                self.settings.muted = !self.settings.muted;
+            }
+            Key::Left | Key::Right | Key::Return if item == SettingsItem::SaveFormat => {
+                self.settings.save_format = self.settings.save_format.other();
+            }
+            Key::Left if item == SettingsItem::Grid => {
+                self.settings.grid = self.settings.grid.previous();
+            }
+            Key::Right | Key::Return if item == SettingsItem::Grid => {
+                self.settings.grid = self.settings.grid.next();
+            }
+            Key::Left | Key::Right | Key::Return if item == SettingsItem::Hunger => {
+                self.settings.hunger = !self.settings.hunger;
+            }
+            Key::Return | Key::Escape => {
+                self.save_settings();
+                let selected = TitleItem::Settings.index();
+                self.state = GameState::Title { selected };
+            }
+            _ => {}
+        }
+    }
+
+    /// Draws the game state on the screen.
+    ///
+    /// # Arguments
     ///
-    /// This function moves the snake forward in the specified direction if it's alive.
-    /// It also checks if the snake has eaten food and updates the game over status
-    /// if the snake is no longer alive. Finally, it resets the waiting time for the next update.
+    /// * `progress` - The fraction of the current tick that has passed, see `tick_progress`.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    ///
+    /// This function draws the snake, food (if it exists) and borders with the current score,
+    /// or the progress toward the goal of a campaign level, then the overlay of the current
+    /// screen on top: a menu, the paused banner, or the game over screen showing the final
+    /// score. A freshly unlocked achievement is announced on top of everything.
     ///
     /// # Example
     ///
     /// ```rust
-    /// let mut game = Game::new(20, 20);
-    /// game.update_snake(Some(Direction::Right));
+    /// // Assuming you have a valid Context and G2d instance:
+    /// // game.draw(game.tick_progress(), &con, &mut g);
     /// ```
-    fn update_snake(&mut self, dir: Option<Direction>) {
-        if self.check_if_snake_alive(dir) {
-            self.snake.move_forward(dir);
-            self.check_eating();
-        } else {
-            self.game_over = true;
+    pub fn draw(&self, progress: f64, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        let skin = Skin::new(self.settings.snake_style, self.sprites.as_ref());
+        let (width, height) = self.board_size();
+        let preview = self
+            .editor
+            .as_ref()
+            .filter(|_| self.state == GameState::Editor)
+            .map(|editor| editor.preview(width, height));
+        let world = preview.as_ref().unwrap_or(&self.world);
+        let topology = world.topology();
+        for (index, portal) in world.layout.portals.iter().enumerate() {
+            let color = PORTAL_COLORS[index % PORTAL_COLORS.len()];
+            topology.draw_cell(color, portal.a.0, portal.a.1, con, g);
+            topology.draw_cell(color, portal.b.0, portal.b.1, con, g);
+        }
+
+        world.snake.draw(progress, skin, theme, topology, con, g);
+
+        if world.food_exists {
+            let color = world.food_kind.color(theme);
+            topology.draw_cell(color, world.food_x, world.food_y, con, g);
+        }
+
+        for &(x, y) in &world.dropped {
+            topology.draw_cell(theme.food, x, y, con, g);
+        }
+
+        let walls = world.rules.mode().walls(world);
+        for &(x, y) in world.layout.obstacles.iter().chain(&walls) {
+            topology.draw_cell(theme.border, x, y, con, g);
+        }
+
+        for enemy in &world.enemies {
+            topology.draw_cell(theme.enemy, enemy.x, enemy.y, con, g);
+        }
+
+        draw_rectangle(theme.border, 0, 0, width, 1, con, g);
+        draw_rectangle(theme.border, 0, height - 1, width, 1, con, g);
+        draw_rectangle(theme.border, 0, 0, 1, height, con, g);
+        draw_rectangle(theme.border, width - 1, 0, 1, height, con, g);
+
+        if self.state != GameState::Editor {
+            let period = self.speed().moving_period();
+            let status = world.rules.mode().status(world, period);
+            let score = match self.mode {
+                PlayMode::Normal => status,
+                PlayMode::Practice => format!("PRACTICE {}", status),
+                PlayMode::Daily { .. } => format!("DAILY {}", status),
+                PlayMode::Campaign { level } => match self.campaign_level() {
+                    Some(campaign) => {
+                        format!("LEVEL {} {}", level + 1, campaign.goal.progress(world))
+                    }
+                    None => format!("SCORE {}", world.score),
+                },
+            };
+            draw_text(theme.text, &score, 1.0, 0.15, 0.7, con, g);
+            if world.hunger {
+                self.draw_hunger(world, con, g);
+            }
+        }
+
+        let size = [width, height];
+        match self.state {
+            GameState::Title { selected } => {
+                let items: Vec<String> = TitleItem::ALL
+                    .iter()
+                    .map(|item| match item {
+                        TitleItem::Play => format!("PLAY: {}", self.rules.mode().name()),
+                        _ => item.label().to_string(),
+                    })
+                    .collect();
+                draw_menu("SNAKE", &items, Some(selected), theme, size, con, g);
+                draw_hint("ARROWS AND ENTER", theme, size, con, g);
+            }
+            GameState::Levels { selected } => {
+                let mut items: Vec<String> =
+                    self.levels.iter().map(|level| level.name.clone()).collect();
+                items.push(String::from("NEW LEVEL"));
+                draw_menu("LEVELS", &items, Some(selected), theme, size, con, g);
+                draw_hint(
+                    "ENTER TO PLAY, E TO EDIT, ESC FOR MENU",
+                    theme,
+                    size,
+                    con,
+                    g,
+                );
+            }
+            GameState::Campaign { selected } => {
+                let items: Vec<String> = self
+                    .campaign
+                    .levels
+                    .iter()
+                    .enumerate()
+                    .map(|(index, campaign)| {
+                        let name = &campaign.level.name;
+                        if self.campaign.is_completed(index) {
+                            format!("{} - DONE", name)
+                        } else if self.campaign.is_unlocked(index) {
+                            format!("{} - {}", name, campaign.goal.describe())
+                        } else {
+                            String::from("LOCKED")
+                        }
+                    })
+                    .collect();
+                draw_menu("CAMPAIGN", &items, Some(selected), theme, size, con, g);
+                draw_hint("ENTER TO PLAY, ESC FOR MENU", theme, size, con, g);
+            }
+            GameState::Playing => {}
+            GameState::LevelComplete => self.draw_level_complete(con, g),
+            GameState::Paused => {
+                let center_x = self.width as f64 / 2.0;
+                let top = self.height as f64 / 2.0 - 2.0;
+                draw_text_centered(theme.text, "PAUSED", center_x, top, 2.0, con, g);
+                draw_hint("P TO RESUME, S TO SAVE, ESC FOR MENU", theme, size, con, g);
+            }
+            GameState::GameOver => self.draw_game_over(con, g),
+            GameState::Rewinding { steps_back } => {
+                if let Some(death) = self.death.filter(|_| steps_back == 0) {
+                    let topology = self.world.topology();
+                    topology.draw_cell_outline(theme.highlight, death.x, death.y, con, g);
+                }
+
+                let center_x = self.width as f64 / 2.0;
+                let ticks = format!("REWIND -{}", steps_back);
+                draw_text_centered(theme.text, &ticks, center_x, 1.5, 1.0, con, g);
+                draw_hint("LEFT/RIGHT TO STEP, ENTER TO RESUME", theme, size, con, g);
+            }
+            GameState::Settings { selected } => {
+                let items = vec![
+                    format!("SPEED: {}", self.settings.speed.name()),
+                    format!("THEME: {}", theme.name),
+                    format!("SNAKE: {}", self.settings.snake_style.name()),
+                    format!("SOUND: {}", self.settings.effects_volume.level()),
+                    format!("MUSIC: {}", self.settings.music_volume.level()),
+                    format!("MUTE: {}", if self.settings.muted { "ON" } else { "OFF" }),
+                    format!("SAVES: {}", self.settings.save_format.name()),
+                    format!("BOARD: {}", self.settings.grid.name()),
+                    format!(
+                        "HUNGER: {}",
+                        if self.settings.hunger { "ON" } else { "OFF" }
+                    ),
+                    String::from("BACK"),
+                ];
+                draw_menu("SETTINGS", &items, Some(selected), theme, size, con, g);
+                draw_hint("LEFT/RIGHT TO CHANGE", theme, size, con, g);
+            }
+            GameState::HighScores => {
+                let mut items: Vec<String> = self
+                    .high_scores
+                    .scores()
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, score)| format!("{:>2}. {:>5}", rank + 1, score))
+                    .collect();
+                if items.is_empty() {
+                    items.push(String::from("NO SCORES YET"));
+                }
+                draw_menu("HIGH SCORES", &items, None, theme, size, con, g);
+                draw_hint("PRESS ANY KEY", theme, size, con, g);
+            }
+            GameState::Stats => self.draw_stats(con, g),
+            GameState::Editor => {
+                if let Some(editor) = &self.editor {
+                    self.draw_editor(editor, con, g);
+                }
+            }
+        }
+
+        if let Some(achievement) = self.popups.front() {
+            self.draw_popup(achievement, con, g);
         }
-        self.waiting_time = 0.0;
     }
 
-    /// Restarts the game by resetting all necessary state variables.
+    /// Draws the hunger bar in the top wall, on the right.
     ///
-    /// This function resets the snake to its initial position, resets the waiting time,
-    /// repositions the food, and marks the game as not over.
+    /// # Arguments
     ///
-    /// # Example
+    /// * `world` - The `World` whose snake is going hungry.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
     ///
-    /// ```rust
-    /// let mut game = Game::new(20, 20);
-    /// game.restart();
-    /// ```
-    fn restart(&mut self) {
-        self.snake = Snake::new(2, 2);
-        self.waiting_time = 0.0;
-        self.food_exists = true;
-        self.food_x = 6;
-        self.food_y = 4;
-        self.game_over = false;
+    /// The bar is full right after the snake ate and empties as it goes hungry, turning
+    /// to the highlight color for the last quarter before the snake loses a block.
+    fn draw_hunger(&self, world: &World, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        let length = 6.0;
+        let x = world.width as f64 - 1.0 - length;
+        let fed = 1.0 - world.hungry_ticks as f64 / HUNGER_TICKS as f64;
+        let color = if fed > 0.25 {
+            theme.food
+        } else {
+            theme.highlight
+        };
+
+        draw_bar(theme.background, x, 0.75, length, 0.5, con, g);
+        draw_bar(color, x, 0.75, length * fed, 0.5, con, g);
+    }
+
+    /// Draws a small banner announcing an unlocked achievement at the top of the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `achievement` - The `Achievement` that was unlocked.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    fn draw_popup(&self, achievement: &Achievement, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        let center_x = self.width as f64 / 2.0;
+
+        draw_rectangle(theme.overlay, 3, 1, self.width - 6, 4, con, g);
+        draw_text_centered(
+            theme.highlight,
+            "ACHIEVEMENT UNLOCKED",
+            center_x,
+            1.5,
+            0.6,
+            con,
+            g,
+        );
+        draw_text_centered(theme.title, achievement.name, center_x, 2.5, 0.9, con, g);
+        draw_text_centered(
+            theme.text,
+            achievement.description,
+            center_x,
+            3.8,
+            0.5,
+            con,
+            g,
+        );
+    }
+
+    /// Draws the game over overlay with the final score and a restart prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    ///
+    /// The overlay covers the whole game area and the text is centered horizontally,
+    /// starting a little above the vertical middle of the board. The cell where the
+    /// snake died is outlined on top of the overlay and the cause of death is named.
+    /// After a daily challenge, the rank of the run among the day's results is shown
+    /// instead of the high score announcement.
+    fn draw_game_over(&self, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        draw_rectangle(theme.game_over, 0, 0, self.width, self.height, con, g);
+
+        let center_x = self.width as f64 / 2.0;
+        let top = self.height as f64 / 2.0 - 3.0;
+        let score = format!("SCORE: {}", self.world.score);
+
+        draw_text_centered(theme.text, "GAME OVER", center_x, top, 2.0, con, g);
+        if let Some(death) = self.death {
+            let topology = self.world.topology();
+            topology.draw_cell_outline(theme.highlight, death.x, death.y, con, g);
+
+            let cause = death.cause.to_string().to_uppercase();
+            draw_text_centered(theme.highlight, &cause, center_x, top + 2.3, 0.7, con, g);
+        }
+        draw_text_centered(theme.text, &score, center_x, top + 3.5, 1.0, con, g);
+        let announcement = match (self.daily_rank, &self.daily) {
+            (Some(rank), Some(daily)) => Some(format!("#{} ON {}", rank + 1, daily.date())),
+            _ => self
+                .high_score_rank
+                .map(|_| String::from("NEW HIGH SCORE!")),
+        };
+        if let Some(text) = announcement {
+            draw_text_centered(theme.text, &text, center_x, top + 5.0, 0.8, con, g);
+        }
+        draw_text_centered(
+            theme.text,
+            "PRESS R TO RESTART",
+            center_x,
+            top + 6.5,
+            0.8,
+            con,
+            g,
+        );
+    }
+
+    /// Draws the overlay shown after reaching the goal of a campaign level.
+    ///
+    /// # Arguments
+    ///
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    ///
+    /// The overlay names the level and the goal that was reached, and says whether there
+    /// is a next level to move on to.
+    fn draw_level_complete(&self, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        draw_rectangle(theme.overlay, 0, 0, self.width, self.height, con, g);
+        let (PlayMode::Campaign { level }, Some(campaign)) = (self.mode, self.campaign_level())
+        else {
+            return;
+        };
+
+        let center_x = self.width as f64 / 2.0;
+        let top = self.height as f64 / 2.0 - 3.0;
+        let name = &campaign.level.name;
+        let goal = campaign.goal.describe();
+        draw_text_centered(theme.title, "LEVEL COMPLETE", center_x, top, 2.0, con, g);
+        draw_text_centered(theme.text, name, center_x, top + 2.5, 1.0, con, g);
+        draw_text_centered(theme.highlight, &goal, center_x, top + 4.0, 0.8, con, g);
+
+        let next = if level + 1 < self.campaign.levels.len() {
+            "ENTER FOR THE NEXT LEVEL, ESC FOR CAMPAIGN"
+        } else {
+            "CAMPAIGN FINISHED! PRESS ENTER"
+        };
+        let size = [self.width, self.height];
+        draw_hint(next, theme, size, con, g);
+    }
+
+    /// Draws the level editor on top of the level being edited.
+    ///
+    /// # Arguments
+    ///
+    /// * `editor` - The `Editor` holding the level.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    ///
+    /// Food spots and the first tile of an unfinished portal pair are outlined, and so
+    /// is the cell under the mouse cursor. The name of the level and the current tool
+    /// are shown at the top, the keys or the result of the last save at the bottom.
+    fn draw_editor(&self, editor: &Editor, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        let layout = &editor.level.layout;
+        let topology = editor.level.grid.topology();
+
+        for &(x, y) in &layout.food_spots {
+            topology.draw_cell_outline(theme.food, x, y, con, g);
+        }
+
+        if let Some((x, y)) = editor.pending_portal {
+            let color = PORTAL_COLORS[layout.portals.len() % PORTAL_COLORS.len()];
+            topology.draw_cell_outline(color, x, y, con, g);
+        }
+
+        let (width, height) = self.default_size;
+        if let Some([x, y]) = self.cursor {
+            let (x, y) = topology.cell_at(x, y);
+            if x > 0 && y > 0 && x < width - 1 && y < height - 1 {
+                topology.draw_cell_outline(theme.highlight, x, y, con, g);
+            }
+        }
+
+        let status = format!("{} - {}", editor.level.name, editor.tool.name());
+        draw_text(theme.text, &status, 1.0, 0.15, 0.7, con, g);
+
+        let size = [width, height];
+        match &editor.message {
+            Some(message) => draw_hint(&message.to_ascii_uppercase(), theme, size, con, g),
+            None => draw_hint(
+                "1-5 TOOL, G BOARD, Z/Y UNDO/REDO, S SAVE, ESC BACK",
+                theme,
+                size,
+                con,
+                g,
+            ),
+        }
+    }
+
+    /// Draws the statistics screen with the lifetime figures of the player's profile
+    /// and a histogram of the most recent scores.
+    ///
+    /// # Arguments
+    ///
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    ///
+    /// The bars of the histogram are scaled to the best of the recent scores, oldest
+    /// on the left.
+    fn draw_stats(&self, con: &Context, g: &mut G2d) {
+        let theme = self.theme();
+        let size = [self.width, self.height];
+        let profile = &self.profile;
+        draw_rectangle(theme.overlay, 0, 0, self.width, self.height, con, g);
+
+        let center_x = self.width as f64 / 2.0;
+        draw_text_centered(theme.title, "STATISTICS", center_x, 2.0, 2.0, con, g);
+
+        let mut lines = vec![
+            format!("GAMES PLAYED  {}", profile.games_played),
+            format!("FOOD EATEN    {}", profile.food_eaten),
+            format!("LONGEST SNAKE {}", profile.longest_snake),
+            format!("TIME ALIVE    {}", format_duration(profile.time_alive)),
+            format!(
+                "ACHIEVEMENTS  {}/{}",
+                profile.achievements.len(),
+                ACHIEVEMENTS.len()
+            ),
+            String::from("DEATHS"),
+        ];
+        for (cause, count) in &profile.deaths {
+            lines.push(format!("  {:<11} {}", cause, count));
+        }
+        lines.push(String::from("AVERAGE SCORE"));
+        for speed in Speed::ALL {
+            let average = profile
+                .speeds
+                .get(speed.name())
+                .map_or(0.0, |s| s.average());
+            lines.push(format!("  {:<11} {:.1}", speed.name(), average));
+        }
+
+        for (index, line) in lines.iter().enumerate() {
+            let y = 5.0 + index as f64;
+            draw_text(theme.menu_item, line, 4.0, y, 0.7, con, g);
+        }
+
+        let bottom = self.height as f64 - 4.0;
+        let max_height = bottom - 7.5 - lines.len() as f64;
+        draw_text(
+            theme.text,
+            "RECENT SCORES",
+            4.0,
+            bottom - max_height - 1.5,
+            0.7,
+            con,
+            g,
+        );
+
+        let best = profile
+            .recent_scores
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let bar_width = (self.width as f64 - 8.0) / RECENT_SCORES as f64;
+        for (index, &score) in profile.recent_scores.iter().enumerate() {
+            let x = 4.0 + index as f64 * bar_width;
+            let height = (score as f64 / best as f64 * max_height).max(0.1);
+            draw_bar(
+                theme.menu_selected,
+                x,
+                bottom,
+                bar_width * 0.8,
+                height,
+                con,
+                g,
+            );
+        }
+
+        draw_hint("PRESS ANY KEY", theme, size, con, g);
+    }
+
+    /// Returns how far the game is into the current tick.
+    ///
+    /// # Returns
+    ///
+    /// The time since the last move as a fraction of the moving period, from 0 right
+    /// after the snake moved to 1 when it is about to move again. Outside of a game, and
+    /// once the game is over, the snake is at rest and this is always 1.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let game = Game::new(20, 20);
+    /// assert_eq!(game.tick_progress(), 1.0);
+    /// ```
+    pub fn tick_progress(&self) -> f64 {
+        match self.state {
+            GameState::Playing | GameState::Paused => self.clock.progress(self.moving_period()),
+            _ => 1.0,
+        }
+    }
+
+    /// Updates the game state based on the elapsed time.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_time` - A floating-point number representing the time elapsed since the last update.
+    ///
+    /// Achievement popups time out on every screen. Apart from that, nothing happens unless
+    /// the snake is being played. Otherwise this function updates the timers, emitting a
+    /// `GameEvent::Survived` event for every full minute alive, and moves the snake once for
+    /// every moving period of the chosen speed that has passed. A slow frame can move the
+    /// snake several times, and time short of a full period carries over to the next
+    /// frame, so the snake keeps the same pace whatever the frame rate. The period is
+    /// looked up again before every move, so once a move has stopped a boost, the moves
+    /// after it in the same frame go at the normal pace. On each move the snake turns
+    /// toward the cell picked with the mouse, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut game = Game::new(20, 20);
+    /// game.update(0.1);
+    /// ```
+    pub fn update(&mut self, delta_time: f64) {
+        if !self.popups.is_empty() {
+            self.popup_time += delta_time;
+            if self.popup_time > POPUP_DURATION {
+                self.popups.pop_front();
+                self.popup_time = 0.0;
+            }
+        }
+
+        if self.state != GameState::Playing {
+            return;
+        }
+
+        let minutes = (self.time_alive / 60.0) as u32;
+        self.time_alive += delta_time;
+        if (self.time_alive / 60.0) as u32 > minutes {
+            self.emit(GameEvent::Survived {
+                minutes: minutes + 1,
+            });
+        }
+
+        self.clock.add_frame(delta_time);
+        while self.state == GameState::Playing && self.clock.next_tick(self.moving_period()) {
+            let dir = self.steer_to_target();
+            self.update_snake(dir);
+        }
+    }
+
+    /// Returns the time in seconds between two moves of the snake, at the pace of `speed`
+    /// or faster while it boosts.
+    fn moving_period(&self) -> f64 {
+        self.world.snake.moving_period(self.speed().moving_period())
+    }
+
+    /// Returns the speed the snake moves at.
+    ///
+    /// This is the speed chosen in the settings, except in the daily challenge, whose
+    /// speed is part of the rules of the day, and on a campaign level, which sets its own.
+    fn speed(&self) -> Speed {
+        match (&self.daily, self.campaign_level()) {
+            (Some(daily), _) => daily.speed,
+            (None, Some(campaign)) => campaign.speed,
+            (None, None) => self.settings.speed,
+        }
+    }
+
+    /// Returns the campaign level being played, if any.
+    fn campaign_level(&self) -> Option<&CampaignLevel> {
+        match self.mode {
+            PlayMode::Campaign { level } => self.campaign.levels.get(level),
+            _ => None,
+        }
+    }
+
+    /// Updates the snake's position and checks for game over conditions.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - An optional `Direction` indicating the direction in which the snake will move next.
+    ///
+    /// This function advances the world by one tick, adds the direction and the outcome
+    /// of the tick to the recording of the game, and emits the events of the tick. It ends
+    /// the game if the snake is no longer alive, emitting a `GameEvent::Died` event. On a
+    /// campaign level, reaching the goal completes the level, see `complete_level`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut game = Game::new(20, 20);
+    /// game.update_snake(Some(Direction::Right));
+    /// ```
+    fn update_snake(&mut self, dir: Option<Direction>) {
+        let tick = self.world.ticks;
+        match self.world.step(dir) {
+            Ok(events) => {
+                self.recording.record(tick, dir, &self.world, &events);
+                for event in events {
+                    self.emit(event);
+                }
+                if self.mode == PlayMode::Practice {
+                    self.history.push(self.world.clone());
+                }
+                if self
+                    .campaign_level()
+                    .is_some_and(|campaign| campaign.goal.is_reached(&self.world))
+                {
+                    self.complete_level();
+                }
+            }
+            Err(death) => {
+                self.death = Some(death);
+                self.emit(GameEvent::Died {
+                    score: self.world.score,
+                    death,
+                });
+                if self.mode == PlayMode::Practice {
+                    self.state = GameState::Rewinding { steps_back: 0 };
+                } else {
+                    self.end_game(death);
+                }
+            }
+        }
+    }
+
+    /// Switches to the level complete screen after reaching the goal of a campaign level.
+    ///
+    /// The next level is unlocked and the progress saved to disk the first time the
+    /// level is completed, and a `GameEvent::LevelCompleted` event is emitted.
+    fn complete_level(&mut self) {
+        let PlayMode::Campaign { level } = self.mode else {
+            return;
+        };
+
+        self.state = GameState::LevelComplete;
+        self.emit(GameEvent::LevelCompleted { level });
+        if self.campaign.complete(level) {
+            if let Err(e) = self.campaign.save() {
+                eprintln!("failed to save the campaign progress: {}", e);
+            }
+        }
+    }
+
+    /// Publishes a game event after checking it against the achievements.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `GameEvent` to publish.
+    ///
+    /// Achievements unlocked for the first time are saved in the player's profile,
+    /// queued for a popup, and announced with a `GameEvent::AchievementUnlocked` event
+    /// after the event that unlocked them. Achievements cannot be earned in practice
+    /// mode, where the game can be rewound.
+    fn emit(&mut self, event: GameEvent) {
+        let unlocked = self.achievements.handle_event(&event);
+        self.events.publish(event);
+        if self.mode == PlayMode::Practice {
+            return;
+        }
+
+        for achievement in unlocked {
+            if !self.profile.unlock(achievement.id) {
+                continue;
+            }
+
+            self.popups.push_back(achievement);
+            self.events.publish(GameEvent::AchievementUnlocked {
+                name: achievement.name,
+            });
+            if let Err(e) = self.profile.save() {
+                eprintln!("failed to save the profile: {}", e);
+            }
+        }
+    }
+
+    /// Puts the game back to a snapshot from the rewind history.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps_back` - How many ticks back the snapshot is; 0 is the newest one.
+    ///
+    /// The history itself is left untouched, so the player can step forward again.
+    fn show_snapshot(&mut self, steps_back: usize) {
+        let Some(snapshot) = self.history.get(steps_back) else {
+            return;
+        };
+
+        self.world = snapshot.clone();
+        self.state = GameState::Rewinding { steps_back };
+    }
+
+    /// Saves the game in progress to disk and goes back to the title screen.
+    ///
+    /// The game is written in the format chosen in the settings and can be picked up
+    /// again with the resume entry of the title menu. If it cannot be saved, the error
+    /// is reported on the console and the game stays paused.
+    fn suspend_game(&mut self) {
+        let saved = SavedGame {
+            version: SAVE_VERSION,
+            world: self.world.clone(),
+            waiting_time: self.clock.accumulated(),
+            time_alive: self.time_alive,
+            mode: self.mode,
+            recording: self.recording.clone(),
+        };
+
+        match saved.save(self.settings.save_format) {
+            Ok(()) => {
+                let selected = TitleItem::Resume.index();
+                self.state = GameState::Title { selected };
+            }
+            Err(e) => eprintln!("failed to save the game: {}", e),
+        }
+    }
+
+    /// Loads the saved game from disk and continues it, paused.
+    ///
+    /// The save is validated before anything is changed, and removed once the game has
+    /// been resumed so the same game cannot be continued twice. Nothing happens if
+    /// there is no saved game; a save that cannot be resumed is reported on the console.
+    /// A daily challenge can only be resumed with the board of its day, and a campaign
+    /// level with the board of that level. A game on a board laid out like one of the
+    /// levels is taken to be on that level.
+    fn resume_game(&mut self) {
+        let saved = match SavedGame::load(|mode| self.mode_size(mode)) {
+            Some(Ok(saved)) => saved,
+            Some(Err(e)) => {
+                eprintln!("failed to resume the saved game: {}", e);
+                return;
+            }
+            None => return,
+        };
+
+        let (width, height) = self.mode_size(saved.mode);
+        let daily = match saved.mode {
+            PlayMode::Daily { day } => Some(DailyChallenge::for_day(day, width, height)),
+            _ => None,
+        };
+        if daily
+            .as_ref()
+            .is_some_and(|daily| daily.layout() != saved.world.layout)
+        {
+            eprintln!(
+                "failed to resume the saved game: the board does not match the daily challenge"
+            );
+            return;
+        }
+
+        if let PlayMode::Campaign { level } = saved.mode {
+            let campaign = self.campaign.levels.get(level);
+            if !campaign.is_some_and(|campaign| campaign.level.layout == saved.world.layout) {
+                eprintln!(
+                    "failed to resume the saved game: the board does not match the campaign level"
+                );
+                return;
+            }
+        }
+
+        let level = match saved.mode {
+            PlayMode::Normal if saved.world.layout != Layout::default() => {
+                self.levels.iter().position(|level| {
+                    level.grid == saved.world.grid && level.layout == saved.world.layout
+                })
+            }
+            _ => None,
+        };
+
+        self.world = saved.world;
+        (self.width, self.height) = (width, height);
+        self.clock = FixedTimestep::with_accumulated(saved.waiting_time);
+        self.time_alive = saved.time_alive;
+        self.mode = saved.mode;
+        self.daily = daily;
+        self.level = level;
+        self.recording = saved.recording;
+        self.high_score_rank = None;
+        self.daily_rank = None;
+        self.death = None;
+        self.reset_history();
+        self.state = GameState::Paused;
+
+        if let Err(e) = SavedGame::delete() {
+            eprintln!("failed to remove the saved game: {}", e);
+        }
+    }
+
+    /// Makes the theme at the given index of the theme list the active one.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the theme in the list of loaded themes.
+    ///
+    /// The name of the theme is stored in the settings, which are saved when the
+    /// settings screen is left.
+    fn select_theme(&mut self, index: usize) {
+        self.theme_index = index;
+        self.settings.theme = self.themes[index].name.clone();
+    }
+
+    /// Saves the settings, reporting failures on the console.
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save() {
+            eprintln!("failed to save settings: {}", e);
+        }
+    }
+
+    /// Switches to the game over screen and records the final score.
+    ///
+    /// # Arguments
+    ///
+    /// * `death` - How and where the snake died.
+    ///
+    /// The outcome of the game is added to the player's profile, which is saved to disk,
+    /// and its replay is written to `replays/last.json` in the data directory, so it can
+    /// be shared and checked with `--verify`. A daily challenge run is submitted to the
+    /// leaderboard of its day instead of the high score table, see `record_daily`, and a
+    /// campaign level or a game mode other than endless does not count for the high
+    /// scores at all.
+    /// Otherwise, if the score made it into the high score table, its rank is kept so the
+    /// game over screen can announce it, the table is saved to disk, and a
+    /// `GameEvent::NewHighScore` event is emitted.
+    fn end_game(&mut self, death: Death) {
+        self.state = GameState::GameOver;
+
+        let speed = self.speed();
+        self.profile.record(&RunSummary {
+            score: self.world.score,
+            food_eaten: self.world.food_eaten,
+            length: self.world.snake.len(),
+            time_alive: self.time_alive,
+            cause: death.cause,
+            speed,
+        });
+        if let Err(e) = self.profile.save() {
+            eprintln!("failed to save the profile: {}", e);
+        }
+        self.save_replay("replays/last.json", death);
+
+        if self.daily.is_some() {
+            self.record_daily(death);
+            return;
+        }
+        if self.campaign_level().is_some() || !self.world.rules.mode().counts_for_high_scores() {
+            return;
+        }
+
+        self.high_score_rank = self.high_scores.submit(self.world.score);
+        if let Some(rank) = self.high_score_rank {
+            self.emit(GameEvent::NewHighScore {
+                rank,
+                score: self.world.score,
+            });
+            if let Err(e) = self.high_scores.save() {
+                eprintln!("failed to save high scores: {}", e);
+            }
+        }
+    }
+
+    /// Submits a finished daily challenge run to the leaderboard of its day.
+    ///
+    /// # Arguments
+    ///
+    /// * `death` - How and where the snake died, as claimed by the replay.
+    ///
+    /// The rank of the run is kept so the game over screen can show it. When the run is
+    /// the best of the day, its replay is also kept as `replays/daily-<date>.json` in the
+    /// data directory.
+    fn record_daily(&mut self, death: Death) {
+        let Some(daily) = &self.daily else {
+            return;
+        };
+
+        let date = daily.date();
+        let result = DailyResult {
+            score: self.world.score,
+            length: self.world.snake.len(),
+            ticks: self.world.ticks,
+        };
+        self.daily_rank = self.daily_scores.submit(&date, result);
+        if let Err(e) = self.daily_scores.save() {
+            eprintln!("failed to save the daily leaderboard: {}", e);
+        }
+
+        if self.daily_rank == Some(0) {
+            self.save_replay(&format!("replays/daily-{}.json", date), death);
+        }
+    }
+
+    /// Writes the replay of the game that just ended to the data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The file name, relative to the data directory.
+    /// * `death` - How and where the snake died.
+    ///
+    /// The replay of a daily challenge names its date, so verifying it also checks that
+    /// it was played on the rules of that day.
+    fn save_replay(&self, name: &str, death: Death) {
+        let date = self.daily.as_ref().map(DailyChallenge::date);
+        let replay = Replay::new(&self.recording, &self.world, death, date);
+        if let Err(e) = replay.save(name) {
+            eprintln!("failed to save the replay: {}", e);
+        }
+    }
+
+    /// Starts a new game of the given kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The `PlayMode` of the new game.
+    ///
+    /// The board takes the size of the kind of game, see `mode_size`.
+    fn start(&mut self, mode: PlayMode) {
+        self.mode = mode;
+        (self.width, self.height) = self.mode_size(mode);
+        self.daily = match mode {
+            PlayMode::Daily { day } => Some(DailyChallenge::for_day(day, self.width, self.height)),
+            _ => None,
+        };
+        self.level = None;
+        self.restart();
+    }
+
+    /// Starts a new regular game on one of the levels.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the level in the list of levels.
+    fn start_level(&mut self, index: usize) {
+        self.mode = PlayMode::Normal;
+        (self.width, self.height) = self.default_size;
+        self.daily = None;
+        self.level = Some(index);
+        self.restart();
+    }
+
+    /// Returns the size of the board a kind of game is played on.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The `PlayMode` of the game.
+    ///
+    /// # Returns
+    ///
+    /// The width and height of the board of a campaign level, `DAILY_SIZE` for the daily
+    /// challenge, or the size the game was created with for any other game.
+    fn mode_size(&self, mode: PlayMode) -> (i32, i32) {
+        match mode {
+            PlayMode::Campaign { level } => self
+                .campaign
+                .levels
+                .get(level)
+                .map_or(self.default_size, |campaign| {
+                    (campaign.width, campaign.height)
+                }),
+            PlayMode::Daily { .. } => DAILY_SIZE,
+            _ => self.default_size,
+        }
+    }
+
+    /// Starts over the rewind history from the current world in practice mode.
+    fn reset_history(&mut self) {
+        if self.mode == PlayMode::Practice {
+            let period = self.moving_period();
+            self.history = RewindBuffer::new(REWIND_SECONDS, period);
+            self.history.push(self.world.clone());
+        }
+    }
+
+    /// Restarts the game by resetting all necessary state variables.
+    ///
+    /// This function creates a new world with the snake at its initial position, resets
+    /// the timers and the recording, switches to the playing screen, and emits a
+    /// `GameEvent::Restarted` event. The world of a daily challenge comes from the rules
+    /// of its day; any other game gets a random seed, and the board of its level or
+    /// campaign level, or the one chosen in the settings. A regular game off the levels is
+    /// played in the game mode chosen on the title screen, any other in the endless mode.
+    /// The hunger rule applies as chosen in the settings, except in the daily challenge and
+    /// the campaign. In practice mode the rewind history starts over from the initial
+    /// position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut game = Game::new(20, 20);
+    /// game.restart();
+    /// ```
+    fn restart(&mut self) {
+        let seed = self
+            .daily
+            .as_ref()
+            .map_or_else(rand::random, |daily| daily.seed);
+        let level = match self.campaign_level() {
+            Some(campaign) => Some(&campaign.level),
+            None => self.level.and_then(|index| self.levels.get(index)),
+        };
+        self.world = match (&self.daily, level) {
+            (Some(daily), _) => daily.world(self.width, self.height),
+            (None, Some(level)) => World::new(
+                self.width,
+                self.height,
+                level.grid,
+                seed,
+                level.layout.clone(),
+            ),
+            (None, None) => World::new(
+                self.width,
+                self.height,
+                self.settings.grid,
+                seed,
+                Layout::default(),
+            ),
+        };
+        if self.mode == PlayMode::Normal && self.level.is_none() {
+            self.world.rules = self.rules.for_speed(self.speed());
+        }
+        self.world.hunger =
+            self.settings.hunger && self.daily.is_none() && self.campaign_level().is_none();
+        self.clock.reset();
+        self.time_alive = 0.0;
+        self.recording = Recording::new(seed);
+        self.target = None;
+        self.high_score_rank = None;
+        self.daily_rank = None;
+        self.death = None;
+        self.state = GameState::Playing;
+        self.reset_history();
+        self.emit(GameEvent::Restarted);
+    }
+}
+
+/// Formats a number of seconds as hours, minutes and seconds.
+///
+/// # Arguments
+///
+/// * `seconds` - The duration to format.
+///
+/// # Returns
+///
+/// The duration as `M:SS`, or `H:MM:SS` once it reaches an hour.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(format_duration(75.4), "1:15");
+/// assert_eq!(format_duration(3725.0), "1:02:05");
+/// ```
+fn format_duration(seconds: f64) -> String {
+    let total = seconds as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Picks the way a snake turns to get closer to a cell.
+///
+/// # Arguments
+///
+/// * `topology` - The `Topology` of the board.
+/// * `head` - The cell the snake's head is on.
+/// * `current` - The direction the snake is heading.
+/// * `target` - The cell to get closer to.
+///
+/// # Returns
+///
+/// The direction of the board, other than straight back, whose next cell is drawn
+/// nearest to the target, or `None` if that is the way the snake is already heading.
+/// The snake can never turn back on itself, so it reaches a cell behind it by turning
+/// twice.
+///
+/// # Example
+///
+/// ```rust
+/// let dir = steer_toward(Grid::Square.topology(), (5, 5), Direction::Right, (5, 9));
+/// assert_eq!(dir, Some(Direction::Down));
+/// ```
+fn steer_toward(
+    topology: &dyn Topology,
+    head: (i32, i32),
+    current: Direction,
+    target: (i32, i32),
+) -> Option<Direction> {
+    let (to_x, to_y) = topology.position(target.0, target.1);
+    let distance = |dir: Direction| {
+        let (x, y) = topology.step(head.0, head.1, dir);
+        let (from_x, from_y) = topology.position(x, y);
+        (to_x - from_x).powi(2) + (to_y - from_y).powi(2)
+    };
+
+    let turns = topology
+        .directions()
+        .iter()
+        .copied()
+        .filter(|&dir| dir != current && dir != current.opposite());
+    let dir = std::iter::once(current)
+        .chain(turns)
+        .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))?;
+    (dir != current).then_some(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resuming_after_dying_goes_back_to_before_the_fatal_tick() {
+        let mut game = Game::new(20, 20);
+        game.start(PlayMode::Practice);
+        game.world = World::new(20, 20, Grid::Square, 7, Layout::default());
+        game.reset_history();
+
+        game.update_snake(Some(Direction::Up));
+        let before = serde_json::to_string(&game.world).unwrap();
+        game.update_snake(None);
+        assert!(matches!(game.state, GameState::Rewinding { steps_back: 0 }));
+
+        game.rewinding_key_pressed(Key::Return, 0);
+        assert!(matches!(game.state, GameState::Paused));
+        assert_eq!(serde_json::to_string(&game.world).unwrap(), before);
+    }
+
+    #[test]
+    fn steering_on_a_square_grid_turns_toward_the_target() {
+        let square = Grid::Square.topology();
+        let steer = |target| steer_toward(square, (5, 5), Direction::Right, target);
+        assert!(steer((5, 9)) == Some(Direction::Down));
+        assert!(steer((6, 1)) == Some(Direction::Up));
+        assert!(steer((9, 5)).is_none());
+        assert!(steer((9, 6)).is_none());
+    }
+
+    #[test]
+    fn steering_on_a_square_grid_never_turns_back() {
+        let square = Grid::Square.topology();
+        let dir = steer_toward(square, (5, 5), Direction::Right, (1, 5));
+        assert!(matches!(dir, Some(Direction::Up | Direction::Down)));
+    }
+
+    #[test]
+    fn steering_on_a_hex_grid_turns_toward_the_target() {
+        let hex = Grid::Hex.topology();
+        let steer = |target| steer_toward(hex, (5, 4), Direction::Right, target);
+        assert!(steer((6, 6)) == Some(Direction::DownRight));
+        assert!(steer((6, 2)) == Some(Direction::UpRight));
+        assert!(steer((9, 4)).is_none());
+    }
+
+    #[test]
+    fn steering_on_a_hex_grid_never_turns_back() {
+        let hex = Grid::Hex.topology();
+        let dir = steer_toward(hex, (5, 4), Direction::Right, (1, 4));
+        assert!(matches!(dir, Some(Direction::UpLeft | Direction::DownLeft)));
     }
 }
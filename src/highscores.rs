@@ -0,0 +1,88 @@
+use crate::storage::{read_data_file, write_data_file};
+use std::io;
+
+const HIGHSCORES_FILE: &str = "highscores.txt";
+const MAX_ENTRIES: usize = 10;
+
+/// The best scores achieved on this machine, highest first.
+pub struct HighScores {
+    scores: Vec<u32>,
+}
+
+impl HighScores {
+    /// Loads the high score table from the data directory.
+    ///
+    /// # Returns
+    ///
+    /// A `HighScores` instance. A missing or unreadable file gives an empty table,
+    /// and lines that are not valid scores are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let high_scores = HighScores::load();
+    /// ```
+    pub fn load() -> HighScores {
+        let text = read_data_file(HIGHSCORES_FILE).unwrap_or_default();
+        let mut scores: Vec<u32> = text
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+
+        scores.sort_unstable_by(|a, b| b.cmp(a));
+        scores.truncate(MAX_ENTRIES);
+
+        HighScores { scores }
+    }
+
+    /// Saves the high score table to the data directory.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if the file could not be written.
+    pub fn save(&self) -> io::Result<()> {
+        let text: String = self.scores.iter().map(|s| format!("{}\n", s)).collect();
+        write_data_file(HIGHSCORES_FILE, &text)
+    }
+
+    /// Returns the recorded scores, highest first.
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    /// Records a score if it is good enough to enter the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `score` - The final score of a finished game.
+    ///
+    /// # Returns
+    ///
+    /// The zero-based rank of the new entry, or `None` if the score did not make
+    /// it into the table. A score of zero is never recorded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut high_scores = HighScores::load();
+    /// if let Some(rank) = high_scores.submit(12) {
+    ///     println!("new high score at position {}", rank + 1);
+    /// }
+    /// ```
+    pub fn submit(&mut self, score: u32) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+
+        let rank = self.scores.iter().position(|&s| score > s);
+        let rank = match rank {
+            Some(rank) => rank,
+            None if self.scores.len() < MAX_ENTRIES => self.scores.len(),
+            None => return None,
+        };
+
+        self.scores.insert(rank, score);
+        self.scores.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
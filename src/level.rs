@@ -0,0 +1,664 @@
+use crate::enemy::{Enemy, EnemyKind};
+use crate::food::FoodKind;
+use crate::snake::Direction;
+use crate::storage::{data_dir, key_values, write_data_file};
+use crate::topology::Grid;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+
+const LEVELS_DIR: &str = "levels";
+const LEVEL_EXTENSION: &str = "level";
+
+/// The cell the tail of the snake starts in unless a level says otherwise. The snake
+/// starts three blocks long, facing right.
+const DEFAULT_SPAWN: (i32, i32) = (2, 2);
+
+/// The symbols portal pairs are written with in level files, in the order of the pairs.
+const PORTAL_SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// The most portal pairs a level file can hold, one per symbol.
+pub const MAX_PORTALS: usize = PORTAL_SYMBOLS.len();
+
+/// The fewest cells that food can be placed on which the snake must be able to reach
+/// for a level to be playable.
+const MIN_REACHABLE_CELLS: usize = 20;
+
+/// The levels that ship with the game, in the same format as the level files.
+const BUILT_IN: [&str; 2] = [GATES, GUARDS];
+
+const GATES: &str = "\
+name=gates
+row=
+row=
+row=
+row=
+row=
+row=
+row=
+row=....A..................B
+row=
+row=
+row=
+row=
+row=
+row=......################
+row=
+row=
+row=
+row=
+row=
+row=
+row=....B..................A
+";
+
+const GUARDS: &str = "\
+name=guards
+row=
+row=
+row=
+row=
+row=
+row=
+row=..............|
+row=
+row=
+row=....##########....##########
+row=
+row=
+row=
+row=
+row=.............*
+row=
+row=
+row=
+row=
+row=....##########....##########
+row=
+row=
+row=
+row=
+row=..........................@
+";
+
+/// A pair of linked portal tiles.
+///
+/// A snake whose head steps onto either tile comes out of the other one, still moving
+/// in the same direction, and its body follows it through. Food is never placed on a
+/// portal tile.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Portal {
+    pub a: (i32, i32),
+    pub b: (i32, i32),
+}
+
+impl Portal {
+    /// Returns the tile a snake comes out of when entering a cell, if the cell is one of
+    /// the two tiles of this portal.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell entered.
+    /// * `y` - The y-coordinate of the cell entered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let portal = Portal { a: (2, 3), b: (8, 9) };
+    /// assert_eq!(portal.exit(2, 3), Some((8, 9)));
+    /// assert_eq!(portal.exit(4, 4), None);
+    /// ```
+    pub fn exit(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        if self.a == (x, y) {
+            Some(self.b)
+        } else if self.b == (x, y) {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the cell the snake's head ends up in when it steps onto a cell, which is the
+/// linked exit if the cell is a portal tile and the cell itself otherwise.
+///
+/// # Arguments
+///
+/// * `portals` - The portals on the board.
+/// * `cell` - The cell stepped onto.
+///
+/// # Example
+///
+/// ```rust
+/// let portals = [Portal { a: (2, 3), b: (8, 9) }];
+/// assert_eq!(teleport(&portals, (8, 9)), (2, 3));
+/// assert_eq!(teleport(&portals, (4, 4)), (4, 4));
+/// ```
+pub fn teleport(portals: &[Portal], cell: (i32, i32)) -> (i32, i32) {
+    portals
+        .iter()
+        .find_map(|portal| portal.exit(cell.0, cell.1))
+        .unwrap_or(cell)
+}
+
+/// Everything that is placed on a board before the game starts. The enemies are where
+/// they start from, and `spawn` is the cell of the snake's tail.
+///
+/// Food only appears on the food spots, as long as one of them is free, and the first
+/// food is on the first of them. Without food spots, food appears anywhere and the first
+/// food is four cells right and two cells down of the spawn. Every food after the first
+/// is of one of the kinds in `foods`, picked at random when there is more than one.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    pub obstacles: Vec<(i32, i32)>,
+    pub portals: Vec<Portal>,
+    pub enemies: Vec<Enemy>,
+    pub spawn: (i32, i32),
+    pub food_spots: Vec<(i32, i32)>,
+    pub foods: Vec<FoodKind>,
+}
+
+impl Default for Layout {
+    fn default() -> Layout {
+        Layout {
+            obstacles: Vec::new(),
+            portals: Vec::new(),
+            enemies: Vec::new(),
+            spawn: DEFAULT_SPAWN,
+            food_spots: Vec::new(),
+            foods: vec![FoodKind::Normal],
+        }
+    }
+}
+
+impl Layout {
+    /// Checks whether a cell holds an obstacle.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    pub fn is_obstacle(&self, x: i32, y: i32) -> bool {
+        self.obstacles.contains(&(x, y))
+    }
+
+    /// Checks whether a cell is one of the tiles of a portal.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    pub fn is_portal(&self, x: i32, y: i32) -> bool {
+        self.portals
+            .iter()
+            .any(|portal| portal.exit(x, y).is_some())
+    }
+
+    /// Returns the cell the first food is placed on.
+    pub fn first_food(&self) -> (i32, i32) {
+        let (x, y) = self.spawn;
+        self.food_spots.first().copied().unwrap_or((x + 4, y + 2))
+    }
+
+    /// Returns the symbol a cell is written with in a level file.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    fn symbol(&self, x: i32, y: i32) -> char {
+        if self.is_obstacle(x, y) {
+            return '#';
+        }
+        if let Some(index) = self.portals.iter().position(|p| p.exit(x, y).is_some()) {
+            return PORTAL_SYMBOLS.as_bytes()[index] as char;
+        }
+        if let Some(enemy) = self.enemies.iter().find(|e| (e.x, e.y) == (x, y)) {
+            return match (enemy.kind, enemy.direction) {
+                (EnemyKind::Patrol, Direction::Left | Direction::Right) => '-',
+                (EnemyKind::Patrol, _) => '|',
+                (EnemyKind::Ball, _) => '*',
+                (EnemyKind::Chaser, _) => '@',
+            };
+        }
+        if self.food_spots.contains(&(x, y)) {
+            return '+';
+        }
+        '.'
+    }
+
+    /// Checks that the layout fits inside the walls of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if every obstacle, portal tile, enemy and food spot lies inside the walls
+    /// and no two of them share a cell. Otherwise a message naming the first problem.
+    pub fn validate(&self, width: i32, height: i32) -> Result<(), String> {
+        let portal_tiles = self.portals.iter().flat_map(|portal| [portal.a, portal.b]);
+        let enemy_cells = self.enemies.iter().map(|enemy| (enemy.x, enemy.y));
+        let mut taken: Vec<(i32, i32)> = Vec::new();
+
+        for (kind, (x, y)) in self
+            .obstacles
+            .iter()
+            .map(|&cell| ("obstacle", cell))
+            .chain(portal_tiles.map(|cell| ("portal", cell)))
+            .chain(enemy_cells.map(|cell| ("enemy", cell)))
+            .chain(self.food_spots.iter().map(|&cell| ("food spot", cell)))
+        {
+            if x <= 0 || y <= 0 || x >= width - 1 || y >= height - 1 {
+                return Err(format!("{} at ({}, {}) is outside the board", kind, x, y));
+            }
+            if taken.contains(&(x, y)) {
+                return Err(format!("{} at ({}, {}) is on a taken cell", kind, x, y));
+            }
+            taken.push((x, y));
+        }
+
+        Ok(())
+    }
+}
+
+/// A board to play on, with its own grid, obstacles, portals, enemies, spawn and food
+/// spots.
+#[derive(Clone)]
+pub struct Level {
+    pub name: String,
+    pub grid: Grid,
+    pub layout: Layout,
+}
+
+impl Level {
+    /// Parses a level file.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of the level file.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `Level`, or a message describing why the file is not a level that can
+    /// be played on a board of the given size, see `validate`.
+    ///
+    /// The file holds one `key=value` pair per line. `name` names the level and the
+    /// optional `grid` picks the board by name, square by default. The optional `spawn`
+    /// is the `x,y` cell of the snake's tail, `2,2` by default. Each `row` line draws
+    /// the next row of the board inside the walls, from the top left, one character per
+    /// cell: `.` is an empty cell, `#` an obstacle, and a letter or digit that appears
+    /// exactly twice in the level is a pair of portal tiles. Enemies start on `-` for a
+    /// patrol going right, `|` for a patrol going down, `*` for a ball going down and
+    /// right, and `@` for a chaser, and `+` marks a food spot. Missing rows and cells are
+    /// empty. The optional `foods` lists the kinds of food that can appear, separated by
+    /// commas, only `normal` by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let level = Level::parse("name=tiny\nrow=A.#\nrow=..A", 30, 30).unwrap();
+    /// assert_eq!(level.layout.obstacles, vec![(3, 1)]);
+    /// assert!(level.layout.portals[0] == Portal { a: (1, 1), b: (3, 2) });
+    /// ```
+    pub fn parse(text: &str, width: i32, height: i32) -> Result<Level, String> {
+        let mut name = None;
+        let mut grid = Grid::Square;
+        let mut layout = Layout::default();
+        let mut tiles: Vec<(char, (i32, i32))> = Vec::new();
+        let mut y = 0;
+
+        for (key, value) in key_values(text) {
+            match key {
+                "name" => name = Some(value.to_ascii_uppercase()),
+                "grid" => grid = Grid::from_name(value).ok_or(format!("unknown grid {}", value))?,
+                "spawn" => layout.spawn = parse_cell(value).ok_or("the spawn is not x,y")?,
+                "foods" => {
+                    layout.foods = value
+                        .split(',')
+                        .map(|name| {
+                            FoodKind::from_name(name.trim())
+                                .ok_or(format!("unknown food {}", name.trim()))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "row" => {
+                    y += 1;
+                    for (x, symbol) in (1..).zip(value.chars()) {
+                        match symbol {
+                            '.' => {}
+                            '#' => layout.obstacles.push((x, y)),
+                            '+' => layout.food_spots.push((x, y)),
+                            '-' | '|' | '*' | '@' => layout.enemies.push(enemy(symbol, x, y)),
+                            _ if symbol.is_ascii_alphanumeric() => tiles.push((symbol, (x, y))),
+                            _ => return Err(format!("unknown cell '{}' in row {}", symbol, y)),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (index, &(symbol, a)) in tiles.iter().enumerate() {
+            if tiles[..index].iter().any(|&(other, _)| other == symbol) {
+                continue;
+            }
+            let mut others = tiles[index + 1..]
+                .iter()
+                .filter(|&&(other, _)| other == symbol);
+            match (others.next(), others.next()) {
+                (Some(&(_, b)), None) => layout.portals.push(Portal { a, b }),
+                _ => return Err(format!("portal '{}' does not appear exactly twice", symbol)),
+            }
+        }
+
+        let level = Level {
+            name: name.ok_or("the level has no name")?,
+            grid,
+            layout,
+        };
+        level.validate(width, height)?;
+        Ok(level)
+    }
+
+    /// Checks that the level is playable on a board of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if everything fits inside the walls without overlapping, the snake and
+    /// the first food start on free cells, the snake survives its first move, and the
+    /// snake can reach the first food and at least `MIN_REACHABLE_CELLS` cells that food
+    /// can be placed on. Otherwise a message naming the first problem.
+    pub fn validate(&self, width: i32, height: i32) -> Result<(), String> {
+        let world = World::new(width, height, self.grid, 0, self.layout.clone());
+        world.validate(width, height)?;
+        if world.clone().step(None).is_err() {
+            return Err(String::from("the snake crashes on its first move"));
+        }
+
+        let reachable = world.reachable_cells();
+        let food = (world.food_x, world.food_y);
+        if !reachable.contains(&food) {
+            return Err(format!(
+                "the food at ({}, {}) cannot be reached",
+                food.0, food.1
+            ));
+        }
+
+        let room = reachable
+            .iter()
+            .filter(|&&(x, y)| !self.layout.is_portal(x, y))
+            .count();
+        if room < MIN_REACHABLE_CELLS {
+            return Err(format!(
+                "only {} cells can be reached, {} are needed",
+                room, MIN_REACHABLE_CELLS
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the level in the format read by `parse`.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let level = Level::parse("name=tiny\nrow=A.#\nrow=..A", 30, 30).unwrap();
+    /// assert!(level.to_text(30, 30).contains("row=A.#\nrow=..A\n"));
+    /// ```
+    pub fn to_text(&self, width: i32, height: i32) -> String {
+        let (spawn_x, spawn_y) = self.layout.spawn;
+        let mut text = format!(
+            "name={}\ngrid={}\nspawn={},{}\n",
+            self.name.to_ascii_lowercase(),
+            self.grid.name().to_ascii_lowercase(),
+            spawn_x,
+            spawn_y
+        );
+        if self.layout.foods != [FoodKind::Normal] {
+            let names: Vec<&str> = self.layout.foods.iter().map(FoodKind::name).collect();
+            text.push_str(&format!("foods={}\n", names.join(",")));
+        }
+
+        for y in 1..height - 1 {
+            let row: String = (1..width - 1).map(|x| self.layout.symbol(x, y)).collect();
+            text.push_str(&format!("row={}\n", row.trim_end_matches('.')));
+        }
+
+        text
+    }
+
+    /// Writes the level to the `levels` folder of the data directory, where
+    /// `load_levels` finds it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `overwrite` - Whether to replace a file of the same name.
+    ///
+    /// # Returns
+    ///
+    /// The name of the file relative to the data directory, or an error if it could not
+    /// be written. The file is named after the level. Unless `overwrite` is set, an
+    /// existing file is left alone and an error of kind `io::ErrorKind::AlreadyExists`
+    /// is returned.
+    pub fn save(&self, width: i32, height: i32, overwrite: bool) -> io::Result<String> {
+        let stem: String = self
+            .name
+            .to_ascii_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let name = format!("{}/{}.{}", LEVELS_DIR, stem, LEVEL_EXTENSION);
+        if !overwrite && data_dir().join(&name).exists() {
+            let message = format!("{} exists", name);
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, message));
+        }
+        write_data_file(&name, self.to_text(width, height))?;
+        Ok(name)
+    }
+}
+
+/// Returns the built-in levels followed by the levels found on disk.
+///
+/// # Arguments
+///
+/// * `width` - The width of the board, including the walls.
+/// * `height` - The height of the board, including the walls.
+///
+/// # Returns
+///
+/// A `Vec<Level>` of every level that fits the board.
+///
+/// Level files are read from the `levels` folder of the data directory and must have
+/// the `.level` extension. Files that cannot be read or parsed are skipped with a
+/// warning. A level file takes the place of an earlier level of the same name, so a
+/// level saved from the editor replaces the built-in level it was edited from.
+pub fn load_levels(width: i32, height: i32) -> Vec<Level> {
+    let mut levels: Vec<Level> = BUILT_IN
+        .iter()
+        .filter_map(|text| Level::parse(text, width, height).ok())
+        .collect();
+
+    let Ok(entries) = fs::read_dir(data_dir().join(LEVELS_DIR)) else {
+        return levels;
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == LEVEL_EXTENSION))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let level = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Level::parse(&text, width, height));
+        match level {
+            Ok(level) => add_level(&mut levels, level),
+            Err(e) => eprintln!("skipping level {}: {}", path.display(), e),
+        }
+    }
+
+    levels
+}
+
+/// Adds a level to a list, replacing the level of the same name if there is one.
+///
+/// # Arguments
+///
+/// * `levels` - The levels found so far.
+/// * `level` - The `Level` to add.
+fn add_level(levels: &mut Vec<Level>, level: Level) {
+    match levels.iter_mut().find(|known| known.name == level.name) {
+        Some(known) => *known = level,
+        None => levels.push(level),
+    }
+}
+
+/// Creates the enemy drawn with a symbol in a level file.
+///
+/// # Arguments
+///
+/// * `symbol` - One of `-`, `|`, `*` and `@`.
+/// * `x` - The x-coordinate of the cell the enemy starts in.
+/// * `y` - The y-coordinate of the cell the enemy starts in.
+fn enemy(symbol: char, x: i32, y: i32) -> Enemy {
+    let (kind, direction) = match symbol {
+        '-' => (EnemyKind::Patrol, Direction::Right),
+        '|' => (EnemyKind::Patrol, Direction::Down),
+        '*' => (EnemyKind::Ball, Direction::DownRight),
+        _ => (EnemyKind::Chaser, Direction::Right),
+    };
+    Enemy {
+        kind,
+        x,
+        y,
+        direction,
+    }
+}
+
+/// Parses a cell written as `x,y`.
+fn parse_cell(value: &str) -> Option<(i32, i32)> {
+    let (x, y) = value.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_read_from_the_top_left() {
+        let text = "name=tiny\nrow=A.#\nrow=\nrow=..A+\nrow=\nrow=.-|*@";
+        let level = Level::parse(text, 30, 30).ok().unwrap();
+        assert_eq!(level.name, "TINY");
+        assert!(level.grid == Grid::Square);
+        assert_eq!(level.layout.obstacles, vec![(3, 1)]);
+        assert!(
+            level.layout.portals
+                == vec![Portal {
+                    a: (1, 1),
+                    b: (3, 3)
+                }]
+        );
+        assert_eq!(level.layout.food_spots, vec![(4, 3)]);
+        let enemies: Vec<(EnemyKind, i32, i32)> = level
+            .layout
+            .enemies
+            .iter()
+            .map(|enemy| (enemy.kind, enemy.x, enemy.y))
+            .collect();
+        assert!(
+            enemies
+                == vec![
+                    (EnemyKind::Patrol, 2, 5),
+                    (EnemyKind::Patrol, 3, 5),
+                    (EnemyKind::Ball, 4, 5),
+                    (EnemyKind::Chaser, 5, 5),
+                ]
+        );
+    }
+
+    #[test]
+    fn optional_keys_have_defaults() {
+        let level = Level::parse("name=empty", 30, 30).ok().unwrap();
+        assert!(level.layout == Layout::default());
+
+        let text = "name=hex\ngrid=hex\nspawn=3,5\nfoods=normal, bonus";
+        let level = Level::parse(text, 30, 30).ok().unwrap();
+        assert!(level.grid == Grid::Hex);
+        assert_eq!(level.layout.spawn, (3, 5));
+        assert!(level.layout.foods == vec![FoodKind::Normal, FoodKind::Bonus]);
+    }
+
+    #[test]
+    fn broken_levels_are_rejected() {
+        let broken = [
+            ("row=...", "the level has no name"),
+            ("name=x\nrow=..?", "unknown cell '?' in row 1"),
+            (
+                "name=x\nrow=A..A..A",
+                "portal 'A' does not appear exactly twice",
+            ),
+            ("name=x\nrow=A", "portal 'A' does not appear exactly twice"),
+            ("name=x\ngrid=cube", "unknown grid cube"),
+            ("name=x\nfoods=normal,cake", "unknown food cake"),
+            ("name=x\nspawn=2", "the spawn is not x,y"),
+            (
+                "name=x\nrow=\nrow=....#",
+                "the snake crashes on its first move",
+            ),
+        ];
+        for (text, error) in broken {
+            assert_eq!(Level::parse(text, 30, 30).err().as_deref(), Some(error));
+        }
+    }
+
+    #[test]
+    fn levels_round_trip_through_text() {
+        let texts = [
+            "name=tiny\nrow=A.#\nrow=\nrow=..A+\nrow=\nrow=.-|*@",
+            "name=hex maze\ngrid=hex\nspawn=3,5\nfoods=normal,bonus\nrow=##\nrow=\nrow=.B..C\n\
+             row=\nrow=\nrow=\nrow=...B....C..+.....+",
+            "name=empty",
+        ];
+        for text in texts {
+            let level = Level::parse(text, 30, 30).ok().unwrap();
+            let written = level.to_text(30, 30);
+            let reread = Level::parse(&written, 30, 30).ok().unwrap();
+            assert_eq!(reread.name, level.name);
+            assert!(reread.grid == level.grid);
+            assert!(reread.layout == level.layout);
+            assert_eq!(reread.to_text(30, 30), written);
+        }
+    }
+
+    #[test]
+    fn later_levels_replace_levels_of_the_same_name() {
+        let mut levels = Vec::new();
+        for text in ["name=tiny", "name=other", "name=tiny\nrow=..#"] {
+            add_level(&mut levels, Level::parse(text, 30, 30).ok().unwrap());
+        }
+        let names: Vec<&str> = levels.iter().map(|level| level.name.as_str()).collect();
+        assert_eq!(names, ["TINY", "OTHER"]);
+        assert_eq!(levels[0].layout.obstacles, vec![(3, 1)]);
+    }
+}
@@ -1,36 +1,161 @@
 extern crate piston_window;
 extern crate rand;
+extern crate winit;
 
+mod achievements;
+mod audio;
+mod campaign;
+mod daily;
+mod death;
 mod draw;
+mod editor;
+mod enemy;
+mod events;
+mod font;
+mod food;
 mod game;
+mod highscores;
+mod level;
+mod menu;
+mod mode;
+mod profile;
+mod replay;
+mod rewind;
+mod save;
+mod settings;
 mod snake;
+mod sprite;
+mod state;
+mod storage;
+mod theme;
+mod timestep;
+mod topology;
+mod world;
 
-use crate::draw::to_coord_u32;
+use crate::audio::Audio;
+use crate::draw::{board_context, board_position, draw_rectangle, window_size};
 use crate::game::Game;
-use piston_window::types::Color;
+use crate::replay::Replay;
+use crate::sprite::SnakeSprites;
 use piston_window::*;
+use std::cell::RefCell;
+use std::env;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use winit::window::Fullscreen;
 
-const BACK_COLOR: Color = [0.5, 0.5, 0.5, 1.0];
+/// Checks a replay file given on the command line and exits.
+///
+/// # Arguments
+///
+/// * `path` - The path of the replay file.
+///
+/// The result is printed, and the process exits with status 1 if the replay cannot be
+/// read or does not give the result it claims.
+fn verify_replay(path: &str) -> ! {
+    match Replay::load(Path::new(path)).and_then(|replay| replay.verify()) {
+        Ok(end) => {
+            println!("{}: OK, {}", path, end);
+            process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// How many times per second the game is updated. The snake moves at the pace of its
+/// speed, independently of this and of the frame rate.
+const UPDATES_PER_SECOND: u64 = 120;
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let [_, flag, path] = args.as_slice() {
+        if flag == "--verify" {
+            verify_replay(path);
+        }
+    }
+
     let (width, height) = (30, 30);
 
-    let mut window: PistonWindow =
-        WindowSettings::new("Snake", [to_coord_u32(width), to_coord_u32(height)])
-            .exit_on_esc(true)
-            .build()
-            .unwrap();
+    let mut window: PistonWindow = WindowSettings::new("Snake", window_size(width, height))
+        .exit_on_esc(false)
+        .resizable(true)
+        .build()
+        .unwrap();
 
     let mut game = Game::new(width, height);
+    window.set_ups(UPDATES_PER_SECOND);
+    window.set_max_fps(game.settings().max_fps);
+    game.set_sprites(SnakeSprites::load(&mut window.create_texture_context()));
+    let audio = Rc::new(RefCell::new(Audio::new()));
+    let audio_events = Rc::clone(&audio);
+    game.events_mut()
+        .subscribe(move |event| audio_events.borrow_mut().handle_event(event));
+
+    let mut event_log = None;
+    let mut fullscreen = false;
 
     while let Some(event) = window.next() {
         if let Some(Button::Keyboard(key)) = event.press_args() {
-            game.key_pressed(key);
+            if key == Key::F11 {
+                fullscreen = !fullscreen;
+                let mode = fullscreen.then_some(Fullscreen::Borderless(None));
+                window.window.window.set_fullscreen(mode);
+            } else if key == Key::F3 {
+                event_log = match event_log.take() {
+                    Some(id) => {
+                        game.events_mut().unsubscribe(id);
+                        None
+                    }
+                    None => Some(game.events_mut().subscribe(|event| println!("{}", event))),
+                };
+            } else {
+                game.key_pressed(key);
+            }
+        }
+        if let Some(Button::Keyboard(key)) = event.release_args() {
+            game.key_released(key);
         }
 
+        let (width, height) = game.board_size();
+        if let Some(position) = event.mouse_cursor_args() {
+            let size = window.size();
+            let view_size = [size.width, size.height];
+            game.mouse_moved(board_position(position, view_size, width, height));
+        }
+        if let Some(Button::Mouse(button)) = event.press_args() {
+            game.mouse_pressed(button);
+        }
+        if let Some(Button::Mouse(button)) = event.release_args() {
+            game.mouse_released(button);
+        }
+        if let Some(touch) = event.touch_args() {
+            let size = window.size();
+            let view_size = [size.width, size.height];
+            game.mouse_moved(board_position(touch.position(), view_size, width, height));
+            match touch.touch {
+                Touch::Start => game.mouse_pressed(MouseButton::Left),
+                Touch::End | Touch::Cancel => game.mouse_released(MouseButton::Left),
+                Touch::Move => {}
+            }
+        }
+
+        if game.quit_requested() {
+            window.set_should_close(true);
+        }
+
+        audio.borrow_mut().apply_settings(game.settings());
+
         window.draw_2d(&event, |c, g, _| {
-            clear(BACK_COLOR, g);
-            game.draw(&c, g);
+            let theme = game.theme();
+            clear(theme.letterbox, g);
+
+            let board = board_context(&c, width, height);
+            draw_rectangle(theme.background, 0, 0, width, height, &board, g);
+            game.draw(game.tick_progress(), &board, g);
         });
 
         event.update(|arg| {
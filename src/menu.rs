@@ -0,0 +1,71 @@
+use crate::draw::{draw_rectangle, draw_text_centered};
+use crate::theme::Theme;
+use piston_window::{Context, G2d};
+
+/// Draws a full screen menu with a title and a list of items.
+///
+/// # Arguments
+///
+/// * `title` - The heading drawn at the top of the menu.
+/// * `items` - The labels of the menu entries, in display order.
+/// * `selected` - The index of the highlighted entry, if any.
+/// * `theme` - The `Theme` providing the colors of the menu.
+/// * `size` - The width and height of the game area in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// The menu darkens the whole game area and lists the items centered below the
+/// title. The selected entry is highlighted and marked with arrows. Long menus are
+/// spaced more tightly so that they end above the hint line.
+///
+/// # Example
+///
+/// ```rust
+/// let items = vec![String::from("PLAY"), String::from("QUIT")];
+/// // Assuming you have a valid Context and G2d instance:
+/// // draw_menu("SNAKE", &items, Some(0), &Theme::classic(), [30, 30], &con, &mut g);
+/// ```
+pub fn draw_menu(
+    title: &str,
+    items: &[String],
+    selected: Option<usize>,
+    theme: &Theme,
+    size: [i32; 2],
+    con: &Context,
+    g: &mut G2d,
+) {
+    let [width, height] = size;
+    draw_rectangle(theme.overlay, 0, 0, width, height, con, g);
+
+    let center_x = width as f64 / 2.0;
+    let top = height as f64 / 4.0;
+    draw_text_centered(theme.title, title, center_x, top, 2.5, con, g);
+
+    let first = top + 5.0;
+    let room = height as f64 - 4.5 - first;
+    let spacing = (room / items.len().max(1) as f64).min(1.6);
+    for (index, item) in items.iter().enumerate() {
+        let y = first + index as f64 * spacing;
+        if Some(index) == selected {
+            let label = format!("> {} <", item);
+            draw_text_centered(theme.menu_selected, &label, center_x, y, 1.0, con, g);
+        } else {
+            draw_text_centered(theme.menu_item, item, center_x, y, 1.0, con, g);
+        }
+    }
+}
+
+/// Draws a short hint line at the bottom of the game area.
+///
+/// # Arguments
+///
+/// * `text` - The hint to show, such as the keys that can be used.
+/// * `theme` - The `Theme` providing the color of the hint.
+/// * `size` - The width and height of the game area in blocks.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+pub fn draw_hint(text: &str, theme: &Theme, size: [i32; 2], con: &Context, g: &mut G2d) {
+    let [width, height] = size;
+    let y = height as f64 - 3.0;
+    draw_text_centered(theme.menu_item, text, width as f64 / 2.0, y, 0.6, con, g);
+}
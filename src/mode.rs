@@ -0,0 +1,291 @@
+use crate::death::{Death, DeathCause};
+use crate::food::FoodKind;
+use crate::settings::Speed;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+
+/// How long a time attack game lasts, in seconds.
+const TIME_LIMIT: f64 = 60.0;
+
+/// The number of ticks between two rings of wall closing in, in survival.
+const SHRINK_PERIOD: u32 = 150;
+
+/// The walls of survival stop closing in once the space inside them would get narrower
+/// than this many cells.
+const MIN_ARENA: i32 = 10;
+
+/// The rules a game is played by on top of those of the `World`: extra walls, when the
+/// game ends, and how eating is scored.
+///
+/// The hooks only look at the world, which they can change through its own methods, so
+/// a game played by the same rules always goes the same way. This is what keeps replays
+/// of every mode verifiable. `Rules` picks the mode of a game and is what gets saved.
+pub trait GameMode {
+    /// Returns the name of the mode as shown in menus.
+    fn name(&self) -> &'static str;
+
+    /// Checks whether the mode has put a wall in a cell inside the border.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played.
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    fn is_wall(&self, _world: &World, _x: i32, _y: i32) -> bool {
+        false
+    }
+
+    /// Returns every cell inside the border that holds a wall of the mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played.
+    fn walls(&self, world: &World) -> Vec<(i32, i32)> {
+        (1..world.height - 1)
+            .flat_map(|y| (1..world.width - 1).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.is_wall(world, x, y))
+            .collect()
+    }
+
+    /// Returns the points eating a food is worth.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played, before the food is counted.
+    /// * `food` - The `FoodKind` that was eaten.
+    fn points(&self, _world: &World, food: FoodKind) -> u32 {
+        food.points()
+    }
+
+    /// Brings the world in line with the mode at the end of a tick the snake survived.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played.
+    fn update(&self, _world: &mut World) {}
+
+    /// Checks whether the game is over by the rules of the mode, after `update`.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the game goes on, or the `Death` that ends it.
+    fn check(&self, _world: &World) -> Result<(), Death> {
+        Ok(())
+    }
+
+    /// Returns the line shown at the top of the board while playing.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played.
+    /// * `moving_period` - The time in seconds between two moves of the snake at the pace
+    ///   of the game, without boosting.
+    fn status(&self, world: &World, _moving_period: f64) -> String {
+        format!("SCORE {}", world.score)
+    }
+
+    /// Returns whether the final score of a game can enter the high score table.
+    fn counts_for_high_scores(&self) -> bool {
+        false
+    }
+}
+
+/// The classic game, which goes on until the snake runs into something.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Endless;
+
+impl GameMode for Endless {
+    fn name(&self) -> &'static str {
+        "ENDLESS"
+    }
+
+    fn counts_for_high_scores(&self) -> bool {
+        true
+    }
+}
+
+/// Eating as much as possible before the time runs out.
+///
+/// The time limit is counted in ticks, so pausing stops the clock and a replay ends on
+/// the same tick as the game. Ticks moved while boosting count for the time they last,
+/// see `World::elapsed`. A key that turns the snake moves it at once rather than at
+/// the end of its period, so every turn uses up a little more of the time than the
+/// clock on the wall shows.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeAttack {
+    /// The number of ticks the game lasts, at the pace of the game.
+    pub ticks: u32,
+}
+
+impl GameMode for TimeAttack {
+    fn name(&self) -> &'static str {
+        "TIME ATTACK"
+    }
+
+    fn check(&self, world: &World) -> Result<(), Death> {
+        if world.elapsed() < self.ticks as f64 {
+            return Ok(());
+        }
+
+        let (x, y) = world.snake.head_position();
+        let cause = DeathCause::TimeUp;
+        Err(Death { cause, x, y })
+    }
+
+    fn status(&self, world: &World, moving_period: f64) -> String {
+        let left = (self.ticks as f64 - world.elapsed()).max(0.0) * moving_period;
+        let seconds = left.ceil() as u32;
+        format!(
+            "SCORE {}  TIME {}:{:02}",
+            world.score,
+            seconds / 60,
+            seconds % 60
+        )
+    }
+}
+
+/// Staying alive while the walls close in one ring at a time, with every food worth more
+/// for each ring that has closed.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Survival;
+
+impl Survival {
+    /// Returns how many rings of wall have closed in on a world.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` being played.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut world = World::new(30, 30, Grid::Square, 42, Layout::default());
+    /// world.ticks = 310;
+    /// assert_eq!(Survival.rings(&world), 2);
+    /// ```
+    fn rings(&self, world: &World) -> i32 {
+        let narrowest = world.width.min(world.height) - 2;
+        let max_rings = ((narrowest - MIN_ARENA) / 2).max(0);
+        ((world.ticks / SHRINK_PERIOD) as i32).min(max_rings)
+    }
+}
+
+impl GameMode for Survival {
+    fn name(&self) -> &'static str {
+        "SURVIVAL"
+    }
+
+    fn is_wall(&self, world: &World, x: i32, y: i32) -> bool {
+        let rings = self.rings(world);
+        x <= rings || y <= rings || x >= world.width - 1 - rings || y >= world.height - 1 - rings
+    }
+
+    fn points(&self, world: &World, food: FoodKind) -> u32 {
+        food.points() * (1 + self.rings(world) as u32)
+    }
+
+    /// Moves the food and removes the enemies the walls have closed over.
+    fn update(&self, world: &mut World) {
+        world.clear_obstacles();
+    }
+
+    /// The snake is crushed if a ring closes over any part of it.
+    fn check(&self, world: &World) -> Result<(), Death> {
+        let crushed = self
+            .walls(world)
+            .into_iter()
+            .find(|&(x, y)| world.snake.contains(x, y));
+        match crushed {
+            Some((x, y)) => {
+                let cause = DeathCause::Crushed;
+                Err(Death { cause, x, y })
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn status(&self, world: &World, _moving_period: f64) -> String {
+        format!("SCORE {}  x{}", world.score, 1 + self.rings(world))
+    }
+}
+
+/// The mode a game is played in, as saved with the game and its replay.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Rules {
+    Endless(Endless),
+    TimeAttack(TimeAttack),
+    Survival(Survival),
+}
+
+impl Rules {
+    /// Returns every mode, in menu order.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The `Speed` of the game, which sets the number of ticks time attack
+    ///   lasts.
+    pub fn all(speed: Speed) -> [Rules; 3] {
+        let ticks = (TIME_LIMIT / speed.moving_period()).round() as u32;
+        [
+            Rules::Endless(Endless),
+            Rules::TimeAttack(TimeAttack { ticks }),
+            Rules::Survival(Survival),
+        ]
+    }
+
+    /// Returns the `GameMode` that implements the rules.
+    pub fn mode(&self) -> &dyn GameMode {
+        match self {
+            Rules::Endless(mode) => mode,
+            Rules::TimeAttack(mode) => mode,
+            Rules::Survival(mode) => mode,
+        }
+    }
+
+    /// Returns the same mode, fitted to a speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The `Speed` of the game.
+    pub fn for_speed(&self, speed: Speed) -> Rules {
+        Rules::all(speed)[self.index()]
+    }
+
+    /// Returns the next mode, wrapping around after the last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The `Speed` of the game.
+    pub fn next(&self, speed: Speed) -> Rules {
+        let all = Rules::all(speed);
+        all[(self.index() + 1) % all.len()]
+    }
+
+    /// Returns the previous mode, wrapping around before the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The `Speed` of the game.
+    pub fn previous(&self, speed: Speed) -> Rules {
+        let all = Rules::all(speed);
+        all[(self.index() + all.len() - 1) % all.len()]
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Rules::Endless(_) => 0,
+            Rules::TimeAttack(_) => 1,
+            Rules::Survival(_) => 2,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::Endless(Endless)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::death::DeathCause;
+use crate::settings::Speed;
+use crate::storage::{read_data_file, write_data_file};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io;
+
+const PROFILE_FILE: &str = "profile.json";
+
+/// How many of the latest scores are kept for the histogram on the stats screen.
+pub const RECENT_SCORES: usize = 20;
+
+/// The outcome of one finished game, recorded in the profile.
+pub struct RunSummary {
+    pub score: u32,
+    /// The number of foods eaten, which is less than the score once some are worth more
+    /// than a point.
+    pub food_eaten: u32,
+    pub length: usize,
+    pub time_alive: f64,
+    pub cause: DeathCause,
+    pub speed: Speed,
+}
+
+/// The number of games and the sum of their scores at one speed.
+#[derive(Default, Serialize, Deserialize)]
+pub struct SpeedStats {
+    pub games: u32,
+    pub total_score: u64,
+}
+
+impl SpeedStats {
+    /// Returns the average score of the games at this speed, or 0 if there were none.
+    pub fn average(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.total_score as f64 / self.games as f64
+        }
+    }
+}
+
+/// Lifetime statistics of the player on this machine.
+///
+/// Death causes and per-speed figures are keyed by their names, so causes and
+/// speeds added later do not need a new file format.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub games_played: u32,
+    pub food_eaten: u64,
+    pub longest_snake: usize,
+    pub time_alive: f64,
+    pub deaths: BTreeMap<String, u32>,
+    pub speeds: BTreeMap<String, SpeedStats>,
+    pub recent_scores: VecDeque<u32>,
+    pub achievements: BTreeSet<String>,
+}
+
+impl Profile {
+    /// Loads the profile from the data directory.
+    ///
+    /// # Returns
+    ///
+    /// A `Profile` instance. A missing file gives an empty profile; a file that
+    /// cannot be parsed is reported on the console and ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let profile = Profile::load();
+    /// println!("{} games played", profile.games_played);
+    /// ```
+    pub fn load() -> Profile {
+        let Some(text) = read_data_file(PROFILE_FILE) else {
+            return Profile::default();
+        };
+
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            eprintln!("failed to read {}: {}", PROFILE_FILE, e);
+            Profile::default()
+        })
+    }
+
+    /// Saves the profile to the data directory.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if the file could not be written.
+    pub fn save(&self) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        write_data_file(PROFILE_FILE, text)
+    }
+
+    /// Adds the outcome of a finished game to the statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - The `RunSummary` of the game.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut profile = Profile::default();
+    /// profile.record(&RunSummary {
+    ///     score: 4,
+    ///     food_eaten: 4,
+    ///     length: 7,
+    ///     time_alive: 12.5,
+    ///     cause: DeathCause::Wall,
+    ///     speed: Speed::Normal,
+    /// });
+    /// assert_eq!(profile.games_played, 1);
+    /// ```
+    pub fn record(&mut self, run: &RunSummary) {
+        self.games_played += 1;
+        self.food_eaten += run.food_eaten as u64;
+        self.longest_snake = self.longest_snake.max(run.length);
+        self.time_alive += run.time_alive;
+
+        *self.deaths.entry(run.cause.name().to_string()).or_default() += 1;
+
+        let speed = self.speeds.entry(run.speed.name().to_string()).or_default();
+        speed.games += 1;
+        speed.total_score += run.score as u64;
+
+        if self.recent_scores.len() == RECENT_SCORES {
+            self.recent_scores.pop_front();
+        }
+        self.recent_scores.push_back(run.score);
+    }
+
+    /// Marks an achievement as unlocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the achievement.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the achievement was not unlocked before.
+    pub fn unlock(&mut self, id: &str) -> bool {
+        self.achievements.insert(id.to_string())
+    }
+}
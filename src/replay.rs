@@ -0,0 +1,842 @@
+use crate::daily::{parse_date, DailyChallenge, DAILY_SIZE};
+use crate::death::Death;
+use crate::events::GameEvent;
+use crate::level::Layout;
+use crate::mode::Rules;
+use crate::snake::Direction;
+use crate::storage::write_data_file;
+use crate::topology::Grid;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The version of the replay format. Replays of any other version are rejected.
+pub const REPLAY_VERSION: u32 = 11;
+
+/// A direction key the player pressed, and the tick it moved the snake on.
+///
+/// Every other tick of the game moved the snake in its current direction, so the
+/// inputs are all that is needed to play a game again.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Input {
+    /// The number of ticks before the one the key moved the snake on.
+    pub tick: u32,
+    pub direction: Direction,
+}
+
+/// The boost key being pressed or released, and the tick it took effect on.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Boost {
+    /// The number of ticks before the first one played at the new pace.
+    pub tick: u32,
+    /// Whether the snake started boosting, rather than stopped.
+    pub on: bool,
+}
+
+/// The score and length of the snake after a tick.
+///
+/// A checkpoint is recorded on every tick the snake eats, so a replay that does not
+/// play out as claimed can be traced to the first tick where it went differently.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub tick: u32,
+    pub score: u32,
+    pub length: usize,
+}
+
+impl Checkpoint {
+    /// Takes a checkpoint of a world after its latest tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` to take the checkpoint of.
+    pub fn of(world: &World) -> Checkpoint {
+        Checkpoint {
+            tick: world.ticks,
+            score: world.score,
+            length: world.snake.len(),
+        }
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "score {}, length {} after tick {}",
+            self.score, self.length, self.tick
+        )
+    }
+}
+
+/// Everything that is needed to play the current game again: the seed of its world,
+/// the keys the player pressed, when the snake boosted, and the checkpoints reached so
+/// far.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Recording {
+    pub seed: u64,
+    pub inputs: Vec<Input>,
+    pub boosts: Vec<Boost>,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl Recording {
+    /// Starts the recording of a game.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed the world of the game was created with.
+    pub fn new(seed: u64) -> Recording {
+        Recording {
+            seed,
+            ..Recording::default()
+        }
+    }
+
+    /// Records the outcome of a tick the snake survived.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick` - The number of ticks before this one.
+    /// * `dir` - The direction key that moved the snake on this tick, if any.
+    /// * `world` - The `World` after the tick.
+    /// * `events` - The events of the tick.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// let mut recording = Recording::new(42);
+    /// let events = world.step(Some(Direction::Down)).unwrap();
+    /// recording.record(0, Some(Direction::Down), &world, &events);
+    /// assert_eq!(recording.inputs.len(), 1);
+    /// ```
+    pub fn record(
+        &mut self,
+        tick: u32,
+        dir: Option<Direction>,
+        world: &World,
+        events: &[GameEvent],
+    ) {
+        if let Some(direction) = dir {
+            self.inputs.push(Input { tick, direction });
+        }
+
+        if events
+            .iter()
+            .any(|event| matches!(event, GameEvent::FoodEaten { .. }))
+        {
+            self.checkpoints.push(Checkpoint::of(world));
+        }
+    }
+
+    /// Records that the snake started or stopped boosting before the next tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The `World` the boost was changed in.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// let mut recording = Recording::new(42);
+    /// if world.set_boosting(true) {
+    ///     recording.record_boost(&world);
+    /// }
+    /// ```
+    pub fn record_boost(&mut self, world: &World) {
+        self.boosts.push(Boost {
+            tick: world.ticks,
+            on: world.snake.is_boosting(),
+        });
+    }
+}
+
+/// Why a replay could not be read or does not give the result it claims.
+pub enum ReplayError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Version(u32),
+    /// The game the replay starts from is not a valid game, for the reason given.
+    Invalid(String),
+    /// The replay claims to be a daily challenge, but its date is not a valid date.
+    InvalidDate(String),
+    /// The replay claims to be the daily challenge of the given date, but it starts from
+    /// another seed, board size or obstacle layout, or is played by other rules.
+    NotDaily(String),
+    /// The snake is still alive after the tick the replay claims the game ended on.
+    Survived(u32),
+    /// The inputs cannot come from a real game, for the reason given.
+    Inputs {
+        tick: u32,
+        reason: &'static str,
+    },
+    /// The game went differently than claimed. The checkpoints are what the replay
+    /// claims and what re-simulating it gives at the first point they differ; either
+    /// is missing if that game had already ended or had no more checkpoints.
+    Diverged {
+        tick: u32,
+        claimed: Option<Checkpoint>,
+        actual: Option<Checkpoint>,
+    },
+    /// The game ended on the claimed tick with the claimed result, but the snake died
+    /// of something else or somewhere else than claimed.
+    Death {
+        claimed: Death,
+        actual: Death,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "{}", e),
+            ReplayError::Json(e) => write!(f, "malformed replay: {}", e),
+            ReplayError::Version(version) => write!(
+                f,
+                "replay has version {}, but only version {} is supported",
+                version, REPLAY_VERSION
+            ),
+            ReplayError::Invalid(reason) => write!(f, "invalid replay: {}", reason),
+            ReplayError::InvalidDate(date) => write!(f, "invalid date {}", date),
+            ReplayError::NotDaily(date) => {
+                write!(
+                    f,
+                    "replay does not start from the daily challenge of {}",
+                    date
+                )
+            }
+            ReplayError::Survived(tick) => write!(
+                f,
+                "claimed the game ended on tick {}, but the snake is still alive",
+                tick
+            ),
+            ReplayError::Inputs { tick, reason } => {
+                write!(f, "invalid input for tick {}: {}", tick, reason)
+            }
+            ReplayError::Diverged {
+                tick,
+                claimed,
+                actual,
+            } => {
+                write!(f, "diverged on tick {}: ", tick)?;
+                match claimed {
+                    Some(claimed) => write!(f, "claimed {}", claimed)?,
+                    None => write!(f, "claimed nothing more")?,
+                }
+                match actual {
+                    Some(actual) => write!(f, ", but the game gives {}", actual),
+                    None => write!(f, ", but the game has nothing more"),
+                }
+            }
+            ReplayError::Death { claimed, actual } => {
+                write!(f, "claimed the snake {}, but it {}", claimed, actual)
+            }
+        }
+    }
+}
+
+/// The input log of a finished game, with the result the player claims for it.
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// The date of the daily challenge the game was played on, if it was one.
+    pub date: Option<String>,
+    pub seed: u64,
+    pub width: i32,
+    pub height: i32,
+    pub grid: Grid,
+    pub layout: Layout,
+    pub rules: Rules,
+    pub hunger: bool,
+    pub inputs: Vec<Input>,
+    pub boosts: Vec<Boost>,
+    pub checkpoints: Vec<Checkpoint>,
+    pub score: u32,
+    pub length: usize,
+    pub ticks: u32,
+    /// How and where the snake died on the last tick.
+    pub death: Death,
+}
+
+impl Replay {
+    /// Creates the replay of a finished game.
+    ///
+    /// # Arguments
+    ///
+    /// * `recording` - The `Recording` of the game.
+    /// * `world` - The `World` the game ended in.
+    /// * `death` - The `Death` that ended the game.
+    /// * `date` - The date of the daily challenge the game was played on, if any.
+    ///
+    /// # Returns
+    ///
+    /// A `Replay` claiming the final score, length and tick count of the world, and the
+    /// death of the snake.
+    pub fn new(recording: &Recording, world: &World, death: Death, date: Option<String>) -> Replay {
+        let end = Checkpoint::of(world);
+        Replay {
+            version: REPLAY_VERSION,
+            date,
+            seed: recording.seed,
+            width: world.width,
+            height: world.height,
+            grid: world.grid,
+            layout: world.layout.clone(),
+            rules: world.rules,
+            hunger: world.hunger,
+            inputs: recording.inputs.clone(),
+            boosts: recording.boosts.clone(),
+            checkpoints: recording.checkpoints.clone(),
+            score: end.score,
+            length: end.length,
+            ticks: end.tick,
+            death,
+        }
+    }
+
+    /// Reads a replay from a file anywhere on disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the replay file.
+    ///
+    /// # Returns
+    ///
+    /// The `Replay`, or a `ReplayError` saying why it could not be read.
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        let text = fs::read_to_string(path).map_err(ReplayError::Io)?;
+        let replay: Replay = serde_json::from_str(&text).map_err(ReplayError::Json)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::Version(replay.version));
+        }
+
+        Ok(replay)
+    }
+
+    /// Writes the replay to the data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The file name, relative to the data directory.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if the file could not be written.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        write_data_file(name, text)
+    }
+
+    /// Plays the replay again and checks that it gives the claimed result.
+    ///
+    /// # Returns
+    ///
+    /// The final `Checkpoint` if re-simulating the inputs reaches every claimed checkpoint
+    /// and ends with the claimed score, length, number of ticks and death. Otherwise a
+    /// `ReplayError` pointing at the first tick where the game went differently. A replay
+    /// of a daily challenge must also start from the challenge of its date, on a board of
+    /// `DAILY_SIZE`, played by the endless rules without hunger.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let replay = Replay::load(Path::new("daily-2024-03-01.json")).unwrap();
+    /// match replay.verify() {
+    ///     Ok(end) => println!("confirmed {}", end),
+    ///     Err(e) => println!("rejected: {}", e),
+    /// }
+    /// ```
+    pub fn verify(&self) -> Result<Checkpoint, ReplayError> {
+        if let Some(date) = &self.date {
+            let day = parse_date(date).ok_or_else(|| ReplayError::InvalidDate(date.clone()))?;
+            if (self.width, self.height) != DAILY_SIZE {
+                return Err(ReplayError::NotDaily(date.clone()));
+            }
+            let (width, height) = DAILY_SIZE;
+            let challenge = DailyChallenge::for_day(day, width, height);
+            let start = challenge.world(width, height);
+            let rules = self.rules == Rules::default() && !self.hunger;
+            if challenge.seed != self.seed
+                || start.grid != self.grid
+                || start.layout != self.layout
+                || !rules
+            {
+                return Err(ReplayError::NotDaily(date.clone()));
+            }
+        }
+
+        let mut start = World::new(
+            self.width,
+            self.height,
+            self.grid,
+            self.seed,
+            self.layout.clone(),
+        );
+        start.rules = self.rules;
+        start.hunger = self.hunger;
+        start
+            .validate(self.width, self.height)
+            .map_err(ReplayError::Invalid)?;
+        let (world, death, actual) = simulate(start, &self.inputs, &self.boosts, self.ticks)?;
+        let Some(death) = death else {
+            let divergence = first_divergence(self.checkpoints.iter(), actual.iter());
+            return Err(divergence.unwrap_or(ReplayError::Survived(self.ticks)));
+        };
+
+        let end = Checkpoint::of(&world);
+        let claimed_end = Checkpoint {
+            tick: self.ticks,
+            score: self.score,
+            length: self.length,
+        };
+        let claimed = self.checkpoints.iter().chain([&claimed_end]);
+        let actual = actual.iter().chain([&end]);
+        if let Some(divergence) = first_divergence(claimed, actual) {
+            return Err(divergence);
+        }
+        if death != self.death {
+            return Err(ReplayError::Death {
+                claimed: self.death,
+                actual: death,
+            });
+        }
+
+        Ok(end)
+    }
+}
+
+/// The outcome of `simulate`: the final world, the death of the snake if it died, and
+/// the checkpoints of the ticks it ate on.
+pub type Simulation = (World, Option<Death>, Vec<Checkpoint>);
+
+/// Plays a game from its input log until the snake dies.
+///
+/// # Arguments
+///
+/// * `world` - The `World` the game starts from.
+/// * `inputs` - The inputs of the game, in the order they were pressed.
+/// * `boosts` - The changes to the boost of the snake, in the order they were made.
+/// * `max_ticks` - The number of ticks after which to stop if the snake is still alive.
+///
+/// # Returns
+///
+/// The world after the tick the snake died on, together with its `Death` and the
+/// checkpoints of the ticks the snake ate on. If the snake is still alive after
+/// `max_ticks` ticks, the world at that point is returned without a `Death`. A
+/// `ReplayError::Inputs` is returned if the inputs do not fit the game, for example
+/// because they are out of order, continue after the snake died, or move in a direction
+/// the board does not have. The same goes for boosts, which must also change whether
+/// the snake is boosting.
+///
+/// Once the inputs run out the snake keeps going straight. That is fatal on a board with
+/// walls, but on a board that wraps around the snake can go on forever, hence the limit.
+pub fn simulate(
+    mut world: World,
+    inputs: &[Input],
+    boosts: &[Boost],
+    max_ticks: u32,
+) -> Result<Simulation, ReplayError> {
+    let mut recording = Recording::default();
+    let mut inputs = inputs.iter().peekable();
+    let mut boosts = boosts.iter().peekable();
+
+    while world.ticks < max_ticks {
+        while let Some(boost) = boosts.next_if(|boost| boost.tick <= world.ticks) {
+            let reason = if boost.tick < world.ticks {
+                "out of order"
+            } else if !world.set_boosting(boost.on) {
+                "does not change the boost"
+            } else {
+                continue;
+            };
+            return Err(ReplayError::Inputs {
+                tick: boost.tick,
+                reason,
+            });
+        }
+
+        let dir = match inputs.peek() {
+            Some(input) if !world.topology().directions().contains(&input.direction) => {
+                return Err(ReplayError::Inputs {
+                    tick: input.tick,
+                    reason: "not a direction of the board",
+                });
+            }
+            Some(input) if input.tick == world.ticks => inputs.next().map(|i| i.direction),
+            Some(input) if input.tick < world.ticks => {
+                return Err(ReplayError::Inputs {
+                    tick: input.tick,
+                    reason: "out of order",
+                });
+            }
+            _ => None,
+        };
+
+        let tick = world.ticks;
+        match world.step(dir) {
+            Ok(events) => recording.record(tick, dir, &world, &events),
+            Err(death) => {
+                let input = inputs.next().map(|input| input.tick);
+                if let Some(tick) = input.or(boosts.next().map(|boost| boost.tick)) {
+                    return Err(ReplayError::Inputs {
+                        tick,
+                        reason: "comes after the snake died",
+                    });
+                }
+                return Ok((world, Some(death), recording.checkpoints));
+            }
+        }
+    }
+
+    Ok((world, None, recording.checkpoints))
+}
+
+/// Finds the first point where two lists of checkpoints differ.
+///
+/// # Arguments
+///
+/// * `claimed` - The checkpoints a replay claims, ending with its final result.
+/// * `actual` - The checkpoints of re-simulating it, ending with its final result.
+///
+/// # Returns
+///
+/// A `ReplayError::Diverged` at the earlier tick of the first pair of checkpoints
+/// that differ, or `None` if the lists are equal.
+fn first_divergence<'a>(
+    mut claimed: impl Iterator<Item = &'a Checkpoint>,
+    mut actual: impl Iterator<Item = &'a Checkpoint>,
+) -> Option<ReplayError> {
+    loop {
+        let (claimed, actual) = match (claimed.next(), actual.next()) {
+            (None, None) => return None,
+            (Some(c), Some(a)) if c == a => continue,
+            (c, a) => (c.copied(), a.copied()),
+        };
+
+        let tick = claimed
+            .iter()
+            .chain(actual.iter())
+            .map(|checkpoint| checkpoint.tick)
+            .min()
+            .unwrap_or(0);
+        return Some(ReplayError::Diverged {
+            tick,
+            claimed,
+            actual,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daily::format_date;
+    use crate::death::DeathCause;
+    use crate::mode::{Survival, TimeAttack};
+
+    /// Returns the direction that lines the snake up with the food, if it needs to turn.
+    fn toward_food(world: &World) -> Option<Direction> {
+        let (x, y) = world.snake.head_position();
+        let current = world.snake.head_direction();
+        let wanted = if world.food_x > x {
+            Direction::Right
+        } else if world.food_x < x {
+            Direction::Left
+        } else if world.food_y > y {
+            Direction::Down
+        } else {
+            Direction::Up
+        };
+
+        if wanted == current {
+            None
+        } else if wanted == current.opposite() {
+            Some(if y > 2 {
+                Direction::Up
+            } else {
+                Direction::Down
+            })
+        } else {
+            Some(wanted)
+        }
+    }
+
+    /// Plays a game from a world: the snake heads for the food until it has eaten
+    /// `foods` of them, then goes straight on until it dies. Before every tick, the
+    /// snake boosts when `boost` asks for it and it is long enough to.
+    fn play(
+        mut world: World,
+        seed: u64,
+        foods: u32,
+        boost: fn(&World) -> bool,
+    ) -> (Recording, World, Death) {
+        let mut recording = Recording::new(seed);
+        loop {
+            if world.set_boosting(boost(&world)) {
+                recording.record_boost(&world);
+            }
+            let dir = if world.score < foods {
+                toward_food(&world)
+            } else {
+                None
+            };
+            let tick = world.ticks;
+            match world.step(dir) {
+                Ok(events) => recording.record(tick, dir, &world, &events),
+                Err(death) => return (recording, world, death),
+            }
+        }
+    }
+
+    fn replay(seed: u64) -> Replay {
+        let world = World::new(20, 20, Grid::Square, seed, Layout::default());
+        let (recording, world, death) = play(world, seed, 3, |_| false);
+        assert_eq!(world.score, 3);
+        Replay::new(&recording, &world, death, None)
+    }
+
+    fn daily_replay() -> Replay {
+        let day = parse_date("2024-03-01").unwrap();
+        let (width, height) = DAILY_SIZE;
+        let challenge = DailyChallenge::for_day(day, width, height);
+        let world = challenge.world(width, height);
+        let (recording, world, death) = play(world, challenge.seed, 0, |_| false);
+        Replay::new(&recording, &world, death, Some(format_date(day)))
+    }
+
+    #[test]
+    fn recorded_game_verifies() {
+        let replay = replay(7);
+        assert!(replay.death.cause == DeathCause::Wall);
+        let end = replay.verify().ok().unwrap();
+        assert_eq!(
+            (end.score, end.length, end.tick),
+            (replay.score, replay.length, replay.ticks)
+        );
+    }
+
+    /// Plays a game where the snake boosts for a few ticks after its second food, then
+    /// from its fourth food on whenever it is long enough to.
+    fn boosted_replay() -> Replay {
+        let world = World::new(20, 20, Grid::Square, 7, Layout::default());
+        let boost =
+            |world: &World| (world.score == 2 && world.boosted_ticks < 3) || world.score >= 4;
+        let (recording, world, death) = play(world, 7, 6, boost);
+        Replay::new(&recording, &world, death, None)
+    }
+
+    #[test]
+    fn boosted_game_verifies() {
+        let replay = boosted_replay();
+        let switches: Vec<bool> = replay.boosts.iter().map(|boost| boost.on).collect();
+        assert_eq!(switches[..3], [true, false, true]);
+        // Shedding stopped the boost without a recorded change, so it starts again.
+        assert!(switches[3..].iter().all(|&on| on));
+        assert!(replay
+            .boosts
+            .windows(2)
+            .all(|pair| pair[0].tick < pair[1].tick));
+
+        let end = replay.verify().ok().unwrap();
+        assert_eq!(
+            (end.score, end.length, end.tick),
+            (replay.score, replay.length, replay.ticks)
+        );
+    }
+
+    #[test]
+    fn boosted_game_survives_saving() {
+        let replay = boosted_replay();
+        let text = serde_json::to_string(&replay).unwrap();
+        let loaded: Replay = serde_json::from_str(&text).unwrap();
+        assert_eq!(loaded.boosts.len(), replay.boosts.len());
+        assert_eq!(loaded.verify().ok().unwrap().score, replay.score);
+    }
+
+    #[test]
+    fn missing_boost_end_is_rejected() {
+        let mut replay = boosted_replay();
+        replay.boosts.truncate(1);
+        assert!(matches!(replay.verify(), Err(ReplayError::Diverged { .. })));
+    }
+
+    #[test]
+    fn daily_game_verifies() {
+        assert!(daily_replay().verify().is_ok());
+    }
+
+    #[test]
+    fn tampered_checkpoint_is_rejected() {
+        let mut replay = replay(7);
+        let tick = replay.checkpoints[1].tick;
+        replay.checkpoints[1].score += 1;
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Diverged { tick: t, claimed: Some(_), actual: Some(_) }) if t == tick
+        ));
+    }
+
+    #[test]
+    fn tampered_final_score_is_rejected() {
+        let mut replay = replay(7);
+        replay.score += 1;
+        assert!(matches!(replay.verify(), Err(ReplayError::Diverged { .. })));
+    }
+
+    #[test]
+    fn missing_checkpoint_is_rejected() {
+        let mut replay = replay(7);
+        let removed = replay.checkpoints.pop().unwrap();
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Diverged { tick, claimed: Some(_), actual: Some(_) })
+                if tick == removed.tick
+        ));
+    }
+
+    #[test]
+    fn inputs_out_of_order_are_rejected() {
+        let mut replay = replay(7);
+        assert!(replay.inputs.len() >= 2);
+        replay.inputs.swap(0, 1);
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Inputs {
+                reason: "out of order",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn input_after_death_is_rejected() {
+        let mut replay = replay(7);
+        replay.inputs.push(Input {
+            tick: replay.ticks + 5,
+            direction: Direction::Down,
+        });
+        replay.ticks += 10;
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Inputs {
+                reason: "comes after the snake died",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn boost_that_changes_nothing_is_rejected() {
+        let mut replay = replay(7);
+        replay.boosts.push(Boost { tick: 0, on: true });
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Inputs {
+                tick: 0,
+                reason: "does not change the boost"
+            })
+        ));
+    }
+
+    #[test]
+    fn tampered_death_is_rejected() {
+        let mut moved = replay(7);
+        let death = moved.death;
+        moved.death.x += 1;
+        assert!(matches!(
+            moved.verify(),
+            Err(ReplayError::Death { claimed, actual })
+                if claimed.x == death.x + 1 && actual == death
+        ));
+
+        let mut cause = replay(7);
+        cause.death.cause = DeathCause::Obstacle;
+        assert!(matches!(cause.verify(), Err(ReplayError::Death { .. })));
+    }
+
+    #[test]
+    fn early_end_is_rejected() {
+        let mut replay = replay(7);
+        replay.ticks -= 1;
+        let ticks = replay.ticks;
+        assert!(matches!(replay.verify(), Err(ReplayError::Survived(t)) if t == ticks));
+    }
+
+    #[test]
+    fn tampered_rules_are_rejected() {
+        let mut replay = replay(7);
+        replay.rules = Rules::TimeAttack(TimeAttack { ticks: 5 });
+        assert!(matches!(
+            replay.verify(),
+            Err(ReplayError::Inputs {
+                reason: "comes after the snake died",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn date_of_another_game_is_rejected() {
+        let mut replay = replay(7);
+        replay.date = Some("2024-03-01".to_string());
+        assert!(matches!(replay.verify(), Err(ReplayError::NotDaily(_))));
+    }
+
+    #[test]
+    fn invalid_date_is_rejected() {
+        let mut replay = daily_replay();
+        replay.date = Some("2024-02-30".to_string());
+        assert!(matches!(replay.verify(), Err(ReplayError::InvalidDate(_))));
+    }
+
+    #[test]
+    fn daily_with_other_rules_is_rejected() {
+        let mut replay = daily_replay();
+        replay.rules = Rules::Survival(Survival);
+        assert!(matches!(replay.verify(), Err(ReplayError::NotDaily(_))));
+
+        let mut replay = daily_replay();
+        replay.hunger = true;
+        assert!(matches!(replay.verify(), Err(ReplayError::NotDaily(_))));
+    }
+
+    #[test]
+    fn daily_on_another_board_size_is_rejected() {
+        let mut replay = daily_replay();
+        replay.width = 40;
+        assert!(matches!(replay.verify(), Err(ReplayError::NotDaily(_))));
+    }
+
+    #[test]
+    fn first_divergence_finds_the_earliest_tick() {
+        let at = |tick, score| Checkpoint {
+            tick,
+            score,
+            length: 3 + score as usize,
+        };
+        let same = [at(4, 1), at(9, 2)];
+        assert!(first_divergence(same.iter(), same.iter()).is_none());
+
+        let claimed = [at(4, 1), at(12, 2)];
+        let actual = [at(4, 1), at(9, 2)];
+        assert!(matches!(
+            first_divergence(claimed.iter(), actual.iter()),
+            Some(ReplayError::Diverged { tick: 9, .. })
+        ));
+
+        let claimed = [at(4, 1)];
+        assert!(matches!(
+            first_divergence(claimed.iter(), actual.iter()),
+            Some(ReplayError::Diverged {
+                tick: 9,
+                claimed: None,
+                actual: Some(_)
+            })
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::world::World;
+use std::collections::VecDeque;
+
+/// How many seconds of play the practice mode can rewind.
+pub const REWIND_SECONDS: f64 = 5.0;
+
+/// A ring buffer of the most recent snapshots of a game's `World`.
+///
+/// Only the world changes while playing, so restoring a snapshot puts the snake, the
+/// food, the score and the random number generator back without touching the settings,
+/// themes or event subscribers. Once the buffer is full, taking a new snapshot drops
+/// the oldest one.
+pub struct RewindBuffer {
+    snapshots: VecDeque<World>,
+    capacity: usize,
+}
+
+impl RewindBuffer {
+    /// Creates an empty buffer that covers the given length of play.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - How many seconds of play the buffer keeps.
+    /// * `moving_period` - The time between two moves of the snake, in seconds.
+    ///
+    /// # Returns
+    ///
+    /// A `RewindBuffer` holding one snapshot per tick, and always at least one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let buffer = RewindBuffer::new(REWIND_SECONDS, 0.1);
+    /// assert_eq!(buffer.len(), 0);
+    /// ```
+    pub fn new(seconds: f64, moving_period: f64) -> RewindBuffer {
+        let capacity = ((seconds / moving_period).ceil() as usize).max(1);
+
+        RewindBuffer {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the number of snapshots in the buffer.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Adds a snapshot as the newest one, dropping the oldest if the buffer is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The `World` to add.
+    pub fn push(&mut self, snapshot: World) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Returns a snapshot counted back from the newest one.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps_back` - How many ticks to go back; 0 is the newest snapshot.
+    ///
+    /// # Returns
+    ///
+    /// The snapshot, or `None` if the buffer does not reach that far back.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let buffer = RewindBuffer::new(REWIND_SECONDS, 0.1);
+    /// assert!(buffer.get(0).is_none());
+    /// ```
+    pub fn get(&self, steps_back: usize) -> Option<&World> {
+        let index = self.snapshots.len().checked_sub(steps_back + 1)?;
+        self.snapshots.get(index)
+    }
+
+    /// Drops the newest snapshots, so that play can continue from an earlier one.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps_back` - How many of the newest snapshots to drop.
+    pub fn discard_newest(&mut self, steps_back: usize) {
+        let len = self.snapshots.len().saturating_sub(steps_back);
+        self.snapshots.truncate(len);
+    }
+}
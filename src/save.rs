@@ -0,0 +1,314 @@
+use crate::replay::Recording;
+use crate::settings::SaveFormat;
+use crate::state::PlayMode;
+use crate::storage::{read_data_bytes, remove_data_file, write_data_file};
+use crate::world::World;
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// The version of the save format. Saves of any other version are rejected.
+pub const SAVE_VERSION: u32 = 14;
+
+/// The bytes every binary save starts with.
+const BINARY_MAGIC: &[u8; 4] = b"SNKS";
+
+/// The most bytes a binary save is read or written with, so that a damaged length
+/// cannot make the game try to allocate more than any real save holds.
+const BINARY_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// Why a saved game could not be written or resumed.
+pub enum SaveError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    Version(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{}", e),
+            SaveError::Json(e) => write!(f, "malformed JSON save: {}", e),
+            SaveError::Binary(e) => write!(f, "malformed binary save: {}", e),
+            SaveError::Version(version) => write!(
+                f,
+                "save has version {}, but only version {} is supported",
+                version, SAVE_VERSION
+            ),
+            SaveError::Invalid(reason) => write!(f, "invalid save: {}", reason),
+        }
+    }
+}
+
+/// Returns the `bincode` options binary saves are encoded with: the fixed-size,
+/// little-endian integers of `bincode::serialize`, which earlier saves were written
+/// with, read up to `BINARY_LIMIT` bytes. Trailing bytes are allowed, since the header
+/// is read from the front of the whole save.
+fn binary_options() -> impl Options {
+    bincode::options()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .allow_trailing_bytes()
+        .with_limit(BINARY_LIMIT)
+}
+
+/// Only the version of a save, read before the rest so that saves of other
+/// versions are reported as such instead of as malformed.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// A game in progress, as written to disk when the player suspends it.
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub world: World,
+    pub waiting_time: f64,
+    pub time_alive: f64,
+    pub mode: PlayMode,
+    pub recording: Recording,
+}
+
+impl SavedGame {
+    /// Writes the game to the data directory, replacing any earlier save.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The `SaveFormat` to write the game in.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` that is an error if the game could not be encoded or written.
+    ///
+    /// A save in the other format is removed, so there is only ever one game to resume.
+    pub fn save(&self, format: SaveFormat) -> Result<(), SaveError> {
+        let contents = match format {
+            SaveFormat::Json => self.to_json()?.into_bytes(),
+            SaveFormat::Binary => self.to_bytes()?,
+        };
+
+        write_data_file(format.file_name(), contents).map_err(SaveError::Io)?;
+        remove_data_file(format.other().file_name()).map_err(SaveError::Io)
+    }
+
+    /// Reads the saved game from the data directory and validates it.
+    ///
+    /// # Arguments
+    ///
+    /// * `board_size` - The width and height of the board a game of the given kind is
+    ///   resumed on.
+    ///
+    /// # Returns
+    ///
+    /// `None` if there is no saved game, otherwise the game or the reason it cannot
+    /// be resumed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// match SavedGame::load(|_| (30, 30)) {
+    ///     Some(Ok(saved)) => println!("resuming with score {}", saved.world.score),
+    ///     Some(Err(e)) => eprintln!("cannot resume: {}", e),
+    ///     None => {}
+    /// }
+    /// ```
+    pub fn load(
+        board_size: impl Fn(PlayMode) -> (i32, i32),
+    ) -> Option<Result<SavedGame, SaveError>> {
+        [SaveFormat::Binary, SaveFormat::Json]
+            .into_iter()
+            .find_map(|format| {
+                let bytes = read_data_bytes(format.file_name())?;
+                let saved = match format {
+                    SaveFormat::Json => SavedGame::from_json(&String::from_utf8_lossy(&bytes)),
+                    SaveFormat::Binary => SavedGame::from_bytes(&bytes),
+                };
+                Some(saved.and_then(|saved| {
+                    let (width, height) = board_size(saved.mode);
+                    saved.validate(width, height).map(|_| saved)
+                }))
+            })
+    }
+
+    /// Removes the saved game from the data directory, whatever its format.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if an existing save could not be removed.
+    pub fn delete() -> io::Result<()> {
+        remove_data_file(SaveFormat::Json.file_name())?;
+        remove_data_file(SaveFormat::Binary.file_name())
+    }
+
+    /// Encodes the game as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        serde_json::to_string_pretty(self).map_err(SaveError::Json)
+    }
+
+    /// Decodes a game from JSON, rejecting saves of other versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The JSON text of the save.
+    pub fn from_json(text: &str) -> Result<SavedGame, SaveError> {
+        let header: SaveHeader = serde_json::from_str(text).map_err(SaveError::Json)?;
+        if header.version != SAVE_VERSION {
+            return Err(SaveError::Version(header.version));
+        }
+
+        serde_json::from_str(text).map_err(SaveError::Json)
+    }
+
+    /// Encodes the game in the compact binary form.
+    ///
+    /// # Returns
+    ///
+    /// Four magic bytes followed by the game encoded with `bincode`, whose first
+    /// field is the version.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SaveError> {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        binary_options()
+            .serialize_into(&mut bytes, self)
+            .map_err(SaveError::Binary)?;
+        Ok(bytes)
+    }
+
+    /// Decodes a game from the compact binary form, rejecting saves of other versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The contents of the binary save.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SavedGame, SaveError> {
+        let Some(body) = bytes.strip_prefix(BINARY_MAGIC) else {
+            return Err(SaveError::Invalid(String::from("not a binary save")));
+        };
+
+        let header: SaveHeader = binary_options()
+            .deserialize(body)
+            .map_err(SaveError::Binary)?;
+        if header.version != SAVE_VERSION {
+            return Err(SaveError::Version(header.version));
+        }
+
+        binary_options()
+            .deserialize(body)
+            .map_err(SaveError::Binary)
+    }
+
+    /// Checks that the saved game can be resumed on a board of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board.
+    /// * `height` - The height of the board.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the world passes `World::validate` and the timers are sensible values.
+    /// Otherwise a `SaveError::Invalid` naming the first problem.
+    pub fn validate(&self, width: i32, height: i32) -> Result<(), SaveError> {
+        self.world
+            .validate(width, height)
+            .map_err(SaveError::Invalid)?;
+
+        if !self.waiting_time.is_finite() || self.waiting_time < 0.0 {
+            return Err(SaveError::Invalid(format!(
+                "waiting time {} is not a valid duration",
+                self.waiting_time
+            )));
+        }
+
+        if !self.time_alive.is_finite() || self.time_alive < 0.0 {
+            return Err(SaveError::Invalid(format!(
+                "time alive {} is not a valid duration",
+                self.time_alive
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::Layout;
+    use crate::topology::Grid;
+
+    fn saved_game() -> SavedGame {
+        let mut world = World::new(20, 20, Grid::Square, 7, Layout::default());
+        world.step(None).ok().unwrap();
+        SavedGame {
+            version: SAVE_VERSION,
+            world,
+            waiting_time: 0.0,
+            time_alive: 1.5,
+            mode: PlayMode::Normal,
+            recording: Recording::new(7),
+        }
+    }
+
+    #[test]
+    fn binary_save_round_trips() {
+        let bytes = saved_game().to_bytes().ok().unwrap();
+        let loaded = SavedGame::from_bytes(&bytes).ok().unwrap();
+        assert_eq!(loaded.world.ticks, 1);
+        assert_eq!(loaded.time_alive, 1.5);
+    }
+
+    #[test]
+    fn binary_save_keeps_the_legacy_encoding() {
+        let saved = saved_game();
+        let bytes = saved.to_bytes().ok().unwrap();
+        let legacy = bincode::serialize(&saved).unwrap();
+        assert_eq!(bytes[BINARY_MAGIC.len()..], legacy[..]);
+    }
+
+    #[test]
+    fn truncated_binary_save_is_rejected() {
+        let bytes = saved_game().to_bytes().ok().unwrap();
+        for end in 0..bytes.len() {
+            assert!(SavedGame::from_bytes(&bytes[..end]).is_err());
+        }
+    }
+
+    #[test]
+    fn binary_save_of_another_version_is_rejected() {
+        let mut saved = saved_game();
+        saved.version = SAVE_VERSION - 1;
+        let bytes = saved.to_bytes().ok().unwrap();
+        assert!(matches!(
+            SavedGame::from_bytes(&bytes),
+            Err(SaveError::Version(version)) if version == SAVE_VERSION - 1
+        ));
+    }
+
+    #[test]
+    fn garbage_binary_save_is_rejected() {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend(SAVE_VERSION.to_le_bytes());
+        bytes.extend([0xff; 64]);
+        assert!(matches!(
+            SavedGame::from_bytes(&bytes),
+            Err(SaveError::Binary(_))
+        ));
+
+        // The direction of the snake, then a length for its body that no save could hold.
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend(SAVE_VERSION.to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(u64::MAX.to_le_bytes());
+        assert!(matches!(
+            SavedGame::from_bytes(&bytes),
+            Err(SaveError::Binary(_))
+        ));
+        assert!(matches!(
+            SavedGame::from_bytes(b"not a save"),
+            Err(SaveError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+use crate::storage::{key_values, read_data_file, write_data_file};
+use crate::topology::Grid;
+use std::io;
+
+const SETTINGS_FILE: &str = "settings.txt";
+
+/// The frame rate limits that can be set in the settings file.
+const FPS_RANGE: std::ops::RangeInclusive<u64> = 10..=240;
+
+/// How fast the snake moves.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Speed {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl Speed {
+    /// All speeds, from the slowest to the fastest.
+    pub const ALL: [Speed; 3] = [Speed::Slow, Speed::Normal, Speed::Fast];
+
+    /// Returns the time in seconds between two moves of the snake.
+    pub fn moving_period(&self) -> f64 {
+        match *self {
+            Speed::Slow => 0.15,
+            Speed::Normal => 0.1,
+            Speed::Fast => 0.06,
+        }
+    }
+
+    /// Returns the next speed, wrapping around after the fastest one.
+    pub fn next(&self) -> Speed {
+        match *self {
+            Speed::Slow => Speed::Normal,
+            Speed::Normal => Speed::Fast,
+            Speed::Fast => Speed::Slow,
+        }
+    }
+
+    /// Returns the previous speed, wrapping around before the slowest one.
+    pub fn previous(&self) -> Speed {
+        match *self {
+            Speed::Slow => Speed::Fast,
+            Speed::Normal => Speed::Slow,
+            Speed::Fast => Speed::Normal,
+        }
+    }
+
+    /// Returns the name of the speed as shown in menus and stored on disk.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Speed::Slow => "SLOW",
+            Speed::Normal => "NORMAL",
+            Speed::Fast => "FAST",
+        }
+    }
+
+    /// Finds a speed by its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Speed> {
+        Speed::ALL
+            .into_iter()
+            .find(|speed| speed.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// How the snake's body is drawn.
+#[derive(Copy, Clone, PartialEq)]
+pub enum SnakeStyle {
+    Blocks,
+    Shapes,
+    Sprites,
+}
+
+impl SnakeStyle {
+    /// Returns the next style, wrapping around after the last one.
+    pub fn next(&self) -> SnakeStyle {
+        match *self {
+            SnakeStyle::Blocks => SnakeStyle::Shapes,
+            SnakeStyle::Shapes => SnakeStyle::Sprites,
+            SnakeStyle::Sprites => SnakeStyle::Blocks,
+        }
+    }
+
+    /// Returns the previous style, wrapping around before the first one.
+    pub fn previous(&self) -> SnakeStyle {
+        match *self {
+            SnakeStyle::Blocks => SnakeStyle::Sprites,
+            SnakeStyle::Shapes => SnakeStyle::Blocks,
+            SnakeStyle::Sprites => SnakeStyle::Shapes,
+        }
+    }
+
+    /// Returns the name of the style as shown in menus and stored on disk.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SnakeStyle::Blocks => "BLOCKS",
+            SnakeStyle::Shapes => "SHAPES",
+            SnakeStyle::Sprites => "SPRITES",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<SnakeStyle> {
+        [SnakeStyle::Blocks, SnakeStyle::Shapes, SnakeStyle::Sprites]
+            .into_iter()
+            .find(|style| style.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// How a suspended game is written to disk.
+#[derive(Copy, Clone, PartialEq)]
+pub enum SaveFormat {
+    Json,
+    Binary,
+}
+
+impl SaveFormat {
+    /// Returns the other format.
+    pub fn other(&self) -> SaveFormat {
+        match *self {
+            SaveFormat::Json => SaveFormat::Binary,
+            SaveFormat::Binary => SaveFormat::Json,
+        }
+    }
+
+    /// Returns the name of the format as shown in menus and stored on disk.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SaveFormat::Json => "JSON",
+            SaveFormat::Binary => "BINARY",
+        }
+    }
+
+    /// Returns the name of the save file in the data directory.
+    pub fn file_name(&self) -> &'static str {
+        match *self {
+            SaveFormat::Json => "savegame.json",
+            SaveFormat::Binary => "savegame.bin",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<SaveFormat> {
+        [SaveFormat::Json, SaveFormat::Binary]
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// A volume level from 0 (silent) to 10 (full).
+#[derive(Copy, Clone, PartialEq)]
+pub struct Volume(u8);
+
+impl Volume {
+    const MAX: u8 = 10;
+
+    /// Returns the volume one step louder, stopping at full volume.
+    pub fn up(&self) -> Volume {
+        Volume((self.0 + 1).min(Volume::MAX))
+    }
+
+    /// Returns the volume one step quieter, stopping at silence.
+    pub fn down(&self) -> Volume {
+        Volume(self.0.saturating_sub(1))
+    }
+
+    /// Returns the volume as a fraction from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        self.0 as f32 / Volume::MAX as f32
+    }
+
+    /// Returns the volume level as shown in menus and stored on disk.
+    pub fn level(&self) -> u8 {
+        self.0
+    }
+
+    fn from_level(level: &str) -> Option<Volume> {
+        let level: u8 = level.parse().ok()?;
+        Some(Volume(level.min(Volume::MAX)))
+    }
+}
+
+/// Player preferences that persist between runs.
+pub struct Settings {
+    pub speed: Speed,
+    pub theme: String,
+    pub snake_style: SnakeStyle,
+    pub effects_volume: Volume,
+    pub music_volume: Volume,
+    pub muted: bool,
+    pub save_format: SaveFormat,
+    /// The board new games are played on, except for the daily challenge.
+    pub grid: Grid,
+    /// Whether the snake loses its tail when it goes too long without eating, in every
+    /// game but the daily challenge and the campaign.
+    pub hunger: bool,
+    /// The most frames drawn per second. The snake moves at the pace of its speed
+    /// whatever the frame rate. Only set in the settings file.
+    pub max_fps: u64,
+}
+
+impl Settings {
+    /// Loads the settings from the data directory.
+    ///
+    /// # Returns
+    ///
+    /// A `Settings` instance. Missing files, unknown keys and invalid values fall
+    /// back to the defaults.
+    ///
+    /// The file holds one `key=value` pair per line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let settings = Settings::load();
+    /// let period = settings.speed.moving_period();
+    /// ```
+    pub fn load() -> Settings {
+        let mut settings = Settings {
+            speed: Speed::Normal,
+            theme: String::from("CLASSIC"),
+            snake_style: SnakeStyle::Shapes,
+            effects_volume: Volume(8),
+            music_volume: Volume(5),
+            muted: false,
+            save_format: SaveFormat::Binary,
+            grid: Grid::Square,
+            hunger: false,
+            max_fps: 60,
+        };
+
+        let text = read_data_file(SETTINGS_FILE).unwrap_or_default();
+        for (key, value) in key_values(&text) {
+            match key {
+                "speed" => {
+                    if let Some(speed) = Speed::from_name(value) {
+                        settings.speed = speed;
+                    }
+                }
+                "theme" => settings.theme = value.to_string(),
+                "snake_style" => {
+                    if let Some(style) = SnakeStyle::from_name(value) {
+                        settings.snake_style = style;
+                    }
+                }
+                "effects_volume" => {
+                    if let Some(volume) = Volume::from_level(value) {
+                        settings.effects_volume = volume;
+                    }
+                }
+                "music_volume" => {
+                    if let Some(volume) = Volume::from_level(value) {
+                        settings.music_volume = volume;
+                    }
+                }
+                "muted" => settings.muted = value == "true",
+                "save_format" => {
+                    if let Some(format) = SaveFormat::from_name(value) {
+                        settings.save_format = format;
+                    }
+                }
+                "grid" => {
+                    if let Some(grid) = Grid::from_name(value) {
+                        settings.grid = grid;
+                    }
+                }
+                "hunger" => settings.hunger = value == "true",
+                "max_fps" => {
+                    if let Some(fps) = value.parse().ok().filter(|fps| FPS_RANGE.contains(fps)) {
+                        settings.max_fps = fps;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        settings
+    }
+
+    /// Saves the settings to the data directory.
+    ///
+    /// # Returns
+    ///
+    /// An `io::Result` that is an error if the file could not be written.
+    pub fn save(&self) -> io::Result<()> {
+        let text = format!(
+            "speed={}\ntheme={}\nsnake_style={}\neffects_volume={}\nmusic_volume={}\nmuted={}\n\
+             save_format={}\ngrid={}\nhunger={}\nmax_fps={}\n",
+            self.speed.name(),
+            self.theme,
+            self.snake_style.name(),
+            self.effects_volume.level(),
+            self.music_volume.level(),
+            self.muted,
+            self.save_format.name(),
+            self.grid.name(),
+            self.hunger,
+            self.max_fps
+        );
+        write_data_file(SETTINGS_FILE, &text)
+    }
+}
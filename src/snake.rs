@@ -1,17 +1,27 @@
-use crate::draw::draw_block;
-use piston_window::types::Color;
+use crate::level::{teleport, Portal};
+use crate::settings::SnakeStyle;
+use crate::sprite::{draw_segment_shape, SnakeSprites};
+use crate::theme::Theme;
+use crate::topology::Topology;
 use piston_window::{Context, G2d};
-use std::collections::LinkedList;
-
-const SNAKE_COLOR: Color = [0.00, 0.80, 0.00, 1.0];
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 /// Represents the possible directions the snake can move.
-#[derive(Copy, Clone, PartialEq)]
+///
+/// Which of them the snake can actually move in depends on the `Topology` of the
+/// board: square cells use the first four, hexagonal cells left, right and the four
+/// diagonals.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 impl Direction {
     /// Returns the opposite direction.
@@ -36,21 +46,229 @@ impl Direction {
             Direction::Down => Direction::Up,
             Direction::Left => Direction::Right,
             Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
+
+    /// Checks whether changing from this direction to another one is a left turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The `Direction` the snake turns to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert!(Direction::Up.turns_left(Direction::Left));
+    /// assert!(Direction::Right.turns_left(Direction::UpRight));
+    /// assert!(!Direction::Up.turns_left(Direction::Right));
+    /// ```
+    pub fn turns_left(&self, to: Direction) -> bool {
+        let turn = (self.angle() - to.angle()).rem_euclid(360.0);
+        turn > 0.0 && turn < 180.0
+    }
+
+    /// Returns the name of the direction in lowercase, for logs and messages.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+            Direction::UpLeft => "up-left",
+            Direction::UpRight => "up-right",
+            Direction::DownLeft => "down-left",
+            Direction::DownRight => "down-right",
+        }
+    }
+
+    /// Returns the angle of the direction in degrees.
+    ///
+    /// # Returns
+    ///
+    /// The clockwise angle on screen from `Right`, so `Down` is 90 degrees and
+    /// `Up` is 270 degrees. The diagonals are the directions of the neighbours of a
+    /// hexagonal cell, 60 degrees apart from `Left` and `Right`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(Direction::Down.angle(), 90.0);
+    /// ```
+    pub fn angle(&self) -> f64 {
+        match *self {
+            Direction::Right => 0.0,
+            Direction::Down => 90.0,
+            Direction::Left => 180.0,
+            Direction::Up => 270.0,
+            Direction::DownRight => 60.0,
+            Direction::DownLeft => 120.0,
+            Direction::UpLeft => 240.0,
+            Direction::UpRight => 300.0,
+        }
+    }
+
+    /// Returns how far one step in this direction moves on screen, in blocks.
+    ///
+    /// A diagonal step goes one row up or down and half a block sideways, as between
+    /// the shifted rows of hexagonal cells.
+    pub fn offset(&self) -> (f64, f64) {
+        match *self {
+            Direction::Up => (0.0, -1.0),
+            Direction::Down => (0.0, 1.0),
+            Direction::Left => (-1.0, 0.0),
+            Direction::Right => (1.0, 0.0),
+            Direction::UpLeft => (-0.5, -1.0),
+            Direction::UpRight => (0.5, -1.0),
+            Direction::DownLeft => (-0.5, 1.0),
+            Direction::DownRight => (0.5, 1.0),
+        }
+    }
+}
+
+/// How the blocks of the snake are drawn.
+#[derive(Copy, Clone)]
+pub enum Skin<'a> {
+    /// Every block is a plain cell in the snake color.
+    Blocks,
+    /// Procedural shapes for the head, body, corners and tail.
+    Shapes,
+    /// Tiles from a sprite sheet.
+    Sprites(&'a SnakeSprites),
+}
+
+impl<'a> Skin<'a> {
+    /// Picks the skin for the snake style chosen in the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The `SnakeStyle` chosen in the settings.
+    /// * `sprites` - The sprite sheet, if one was loaded.
+    ///
+    /// # Returns
+    ///
+    /// The matching `Skin`. The sprite style falls back to plain blocks if no sprite
+    /// sheet was loaded.
+    pub fn new(style: SnakeStyle, sprites: Option<&'a SnakeSprites>) -> Skin<'a> {
+        match (style, sprites) {
+            (SnakeStyle::Shapes, _) => Skin::Shapes,
+            (SnakeStyle::Sprites, Some(sprites)) => Skin::Sprites(sprites),
+            _ => Skin::Blocks,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// The shape of one block of the snake's body, used to draw it.
+///
+/// Each variant carries the directions, seen from the block itself, in which
+/// the neighbouring blocks lie.
+#[derive(Copy, Clone, PartialEq)]
+pub enum SegmentKind {
+    /// The head, facing its direction of travel.
+    Head(Direction),
+    /// A straight piece; the direction points towards the head.
+    Straight(Direction),
+    /// A turn; the directions point towards the head and towards the tail.
+    Corner(Direction, Direction),
+    /// The tip of the tail; the direction points towards the rest of the body.
+    Tail(Direction),
+}
+
+/// A block of the snake together with the shape it should be drawn with.
+///
+/// The coordinates are the position on screen of the block, in blocks, as given by
+/// `Topology::position`. They lie between two cells for the head and the tail while
+/// these slide from one cell to the next.
+pub struct Segment {
+    pub x: f64,
+    pub y: f64,
+    pub kind: SegmentKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct Block {
     x: i32,
     y: i32,
 }
 
+impl Block {
+    /// Returns the direction in which a block lies on the screen, if it is a direct
+    /// neighbour there.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The block to look at.
+    /// * `topology` - The `Topology` of the board.
+    fn direction_to(&self, other: &Block, topology: &dyn Topology) -> Option<Direction> {
+        topology.direction_between((self.x, self.y), (other.x, other.y))
+    }
+
+    /// Returns the direction to move in from this block to reach another one, counting
+    /// cells next to each other across the walls of a board that wraps around and cells
+    /// reached through a portal.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The block to look at.
+    /// * `topology` - The `Topology` of the board.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `portals` - The portals on the board.
+    fn neighbour_direction(
+        &self,
+        other: &Block,
+        topology: &dyn Topology,
+        width: i32,
+        height: i32,
+        portals: &[Portal],
+    ) -> Option<Direction> {
+        topology.directions().iter().copied().find(|&dir| {
+            let cell = topology.neighbour(self.x, self.y, dir, width, height);
+            teleport(portals, cell) == (other.x, other.y)
+        })
+    }
+}
+
+/// Places a segment part of the way from a block to its neighbour.
+///
+/// # Arguments
+///
+/// * `segment` - The `Segment` to move.
+/// * `from` - The block the segment starts from.
+/// * `dir` - The direction of the neighbour the segment slides into.
+/// * `progress` - How far the segment has slid, from 0 to 1.
+/// * `topology` - The `Topology` of the board.
+fn slide(
+    segment: &mut Segment,
+    from: &Block,
+    dir: Direction,
+    progress: f64,
+    topology: &dyn Topology,
+) {
+    let (x, y) = topology.position(from.x, from.y);
+    let (dx, dy) = dir.offset();
+
+    segment.x = x + dx * progress;
+    segment.y = y + dy * progress;
+}
+
+/// How many times faster a boosting snake moves.
+pub const BOOST_FACTOR: f64 = 2.0;
+
 /// Represents the snake in the game.
+///
+/// The body is stored in a `VecDeque`, so a snake is cheap to clone for snapshots,
+/// and it can be serialized to suspend a game. Each snake has its own pace, which is
+/// faster while it boosts.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Snake {
     direction: Direction,
-    body: LinkedList<Block>,
+    body: VecDeque<Block>,
     tail: Option<Block>,
+    boosting: bool,
 }
 
 impl Snake {
@@ -77,7 +295,7 @@ impl Snake {
     /// This function initializes the snake with a body of three blocks, starting from
     /// the given `(x, y)` coordinates and extending to the right.
     pub fn new(x: i32, y: i32) -> Snake {
-        let mut body: LinkedList<Block> = LinkedList::new();
+        let mut body: VecDeque<Block> = VecDeque::new();
         body.push_back(Block { x: x + 2, y });
         body.push_back(Block { x: x + 1, y });
         body.push_back(Block { x, y });
@@ -86,6 +304,7 @@ impl Snake {
             direction: Direction::Right,
             body,
             tail: None,
+            boosting: false,
         }
     }
 
@@ -93,21 +312,134 @@ impl Snake {
     ///
     /// # Arguments
     ///
+    /// * `progress` - The fraction of the current tick that has passed, from 0 to 1.
+    /// * `skin` - The `Skin` to draw the blocks with.
+    /// * `theme` - The `Theme` providing the colors of the snake.
+    /// * `topology` - The `Topology` of the board.
     /// * `con` - A reference to the `Context` for rendering.
     /// * `g` - A mutable reference to the `G2d` graphics backend.
     ///
-    /// This function iterates over each block in the snake's body and draws it using the `draw_block` function.
+    /// This function iterates over the segments of the snake's body and draws each one
+    /// as a plain cell, as a procedural shape, or with the matching sprite. Shapes and
+    /// sprites are made for square cells, so on other boards every segment is drawn as a
+    /// plain cell. The head and the tail slide between cells according to `progress`,
+    /// see `segments`.
     ///
     /// # Example
     ///
     /// ```rust
     /// // Assuming you have a valid Context and G2d instance:
-    /// // snake.draw(&con, &mut g);
+    /// // snake.draw(0.5, Skin::Shapes, &Theme::classic(), Grid::Square.topology(), &con, &mut g);
     /// ```
-    pub fn draw(&self, con: &Context, g: &mut G2d) {
-        for block in &self.body {
-            draw_block(SNAKE_COLOR, block.x, block.y, con, g);
+    pub fn draw(
+        &self,
+        progress: f64,
+        skin: Skin,
+        theme: &Theme,
+        topology: &dyn Topology,
+        con: &Context,
+        g: &mut G2d,
+    ) {
+        let skin = if topology.square_cells() {
+            skin
+        } else {
+            Skin::Blocks
+        };
+
+        for segment in self.segments(progress, topology) {
+            match skin {
+                Skin::Blocks => topology.fill(theme.snake, segment.x, segment.y, con, g),
+                Skin::Shapes => draw_segment_shape(&segment, theme, con, g),
+                Skin::Sprites(sprites) => sprites.draw_segment(&segment, con, g),
+            }
+        }
+    }
+
+    /// Returns the blocks of the snake together with the shape of each one.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The fraction of the current tick that has passed, from 0 to 1.
+    /// * `topology` - The `Topology` of the board.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<Segment>` from the head to the tail.
+    ///
+    /// The head faces the direction of travel and the last block is the tail.
+    /// Every other block is a corner if its neighbours are not in a straight line.
+    /// Blocks whose neighbours are not adjacent on screen, such as where the snake
+    /// wraps around the board, are drawn straight.
+    ///
+    /// Once the snake has moved, the head is drawn sliding from the cell it left
+    /// into its current cell, and the tail sliding from the cell it left into the
+    /// last block of the body, so the snake moves smoothly while its blocks stay
+    /// on the grid. The tail does not slide right after the snake has grown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let snake = Snake::new(2, 2);
+    /// let segments = snake.segments(1.0, Grid::Square.topology());
+    /// assert!(segments[0].kind == SegmentKind::Head(Direction::Right));
+    /// assert!(segments[2].kind == SegmentKind::Tail(Direction::Right));
+    /// ```
+    pub fn segments(&self, progress: f64, topology: &dyn Topology) -> Vec<Segment> {
+        let blocks: Vec<&Block> = self.body.iter().collect();
+        let last = blocks.len() - 1;
+
+        let left_tail = self.tail.as_ref().filter(|tail| {
+            *tail != blocks[last] && tail.direction_to(blocks[last], topology).is_some()
+        });
+
+        let mut segments: Vec<Segment> = blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block)| {
+                let kind = if index == 0 {
+                    SegmentKind::Head(self.direction)
+                } else {
+                    let to_head = block.direction_to(blocks[index - 1], topology);
+                    let to_tail = match blocks.get(index + 1) {
+                        Some(next) => block.direction_to(next, topology),
+                        None => left_tail.and_then(|tail| block.direction_to(tail, topology)),
+                    };
+
+                    if index == last && left_tail.is_none() {
+                        SegmentKind::Tail(to_head.unwrap_or(self.direction))
+                    } else {
+                        match (to_head, to_tail) {
+                            (Some(h), Some(t)) if h != t.opposite() => SegmentKind::Corner(h, t),
+                            (Some(h), _) => SegmentKind::Straight(h),
+                            (None, Some(t)) => SegmentKind::Straight(t.opposite()),
+                            (None, None) => SegmentKind::Straight(self.direction),
+                        }
+                    }
+                };
+
+                let (x, y) = topology.position(block.x, block.y);
+                Segment { x, y, kind }
+            })
+            .collect();
+
+        if self.tail.is_some() && last > 0 {
+            if let Some(dir) = blocks[1].direction_to(blocks[0], topology) {
+                slide(&mut segments[0], blocks[1], dir, progress, topology);
+            }
+        }
+
+        if let Some(tail) = left_tail {
+            let dir = tail.direction_to(blocks[last], topology).unwrap();
+            let mut segment = Segment {
+                x: 0.0,
+                y: 0.0,
+                kind: SegmentKind::Tail(dir),
+            };
+            slide(&mut segment, tail, dir, progress, topology);
+            segments.push(segment);
         }
+
+        segments
     }
 
     /// Returns the position of the snake's head.
@@ -136,10 +468,15 @@ impl Snake {
     /// # Arguments
     ///
     /// * `dir` - An optional `Direction` indicating the direction in which the snake will move next.
-    ///           If `None`, the snake continues moving in its current direction.
+    ///   If `None`, the snake continues moving in its current direction.
+    /// * `topology` - The `Topology` of the board.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `portals` - The portals on the board.
     ///
     /// This function updates the snake's direction if a new direction is provided,
-    /// calculates the new position of the head based on the current direction,
+    /// calculates the new position of the head with `next_head`, which may be on the
+    /// other side of a portal,
     /// and moves the snake's body forward by adding a new block at the head's new position
     /// and removing the block at the tail. The removed block is stored in `tail` for growth purposes.
     ///
@@ -147,38 +484,70 @@ impl Snake {
     ///
     /// ```rust
     /// let mut snake = Snake::new(2, 2);
-    /// snake.move_forward(Some(Direction::Up));
+    /// snake.move_forward(Some(Direction::Down), Grid::Square.topology(), 20, 20, &[]);
     /// ```
-    pub fn move_forward(&mut self, dir: Option<Direction>) {
+    pub fn move_forward(
+        &mut self,
+        dir: Option<Direction>,
+        topology: &dyn Topology,
+        width: i32,
+        height: i32,
+        portals: &[Portal],
+    ) {
+        let (x, y) = self.next_head(dir, topology, width, height, portals);
         if let Some(d) = dir {
             self.direction = d
         }
 
-        let (last_x, last_y): (i32, i32) = self.head_position();
-
-        let new_block = match self.direction {
-            Direction::Up => Block {
-                x: last_x,
-                y: last_y - 1,
-            },
-            Direction::Down => Block {
-                x: last_x,
-                y: last_y + 1,
-            },
-            Direction::Left => Block {
-                x: last_x - 1,
-                y: last_y,
-            },
-            Direction::Right => Block {
-                x: last_x + 1,
-                y: last_y,
-            },
-        };
-        self.body.push_front(new_block);
+        self.body.push_front(Block { x, y });
         let removed_block = self.body.pop_back().unwrap();
         self.tail = Some(removed_block);
     }
 
+    /// Returns the number of blocks in the snake's body.
+    pub fn len(&self) -> usize {
+        self.body.len()
+    }
+
+    /// Returns whether the snake is boosting.
+    pub fn is_boosting(&self) -> bool {
+        self.boosting
+    }
+
+    /// Starts or stops boosting.
+    ///
+    /// # Arguments
+    ///
+    /// * `boosting` - `true` to move faster, `false` to go back to the normal pace.
+    pub fn set_boosting(&mut self, boosting: bool) {
+        self.boosting = boosting;
+    }
+
+    /// Returns the time in seconds between two moves of the snake.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The time between two moves at the speed of the game.
+    ///
+    /// # Returns
+    ///
+    /// `base`, or `BOOST_FACTOR` times less while the snake is boosting.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut snake = Snake::new(2, 2);
+    /// snake.set_boosting(true);
+    /// assert_eq!(snake.moving_period(0.1), 0.05);
+    /// ```
+    pub fn moving_period(&self, base: f64) -> f64 {
+        if self.boosting {
+            base / BOOST_FACTOR
+        } else {
+            base
+        }
+    }
+
     /// Returns the current direction of the snake's head.
     ///
     /// # Returns
@@ -203,22 +572,37 @@ impl Snake {
     /// # Arguments
     ///
     /// * `dir` - An optional `Direction` indicating the direction in which the snake will move next.
-    ///           If `None`, the snake continues moving in its current direction.
+    ///   If `None`, the snake continues moving in its current direction.
+    /// * `topology` - The `Topology` of the board.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `portals` - The portals on the board.
     ///
     /// # Returns
     ///
     /// A tuple `(i32, i32)` representing the x and y coordinates of the snake's head after moving in the specified direction.
     ///
-    /// This function determines the next position of the snake's head based on the current direction or the provided direction.
+    /// This function determines the next position of the snake's head based on the current direction or the provided direction,
+    /// using `Topology::neighbour`. On a board that wraps around, the head comes back in on the other side. A head
+    /// that steps onto a portal tile comes out of the linked tile instead.
     ///
     /// # Example
     ///
     /// ```rust
     /// let snake = Snake::new(2, 2);
-    /// let (next_x, next_y) = snake.next_head(Some(Direction::Up));
-    /// assert_eq!((next_x, next_y), (2, 1));
+    /// let topology = Grid::Square.topology();
+    /// assert_eq!(snake.next_head(Some(Direction::Up), topology, 20, 20, &[]), (2, 1));
+    /// let portals = [Portal { a: (2, 1), b: (9, 9) }];
+    /// assert_eq!(snake.next_head(Some(Direction::Up), topology, 20, 20, &portals), (9, 9));
     /// ```
-    pub fn next_head(&self, dir: Option<Direction>) -> (i32, i32) {
+    pub fn next_head(
+        &self,
+        dir: Option<Direction>,
+        topology: &dyn Topology,
+        width: i32,
+        height: i32,
+        portals: &[Portal],
+    ) -> (i32, i32) {
         let (head_x, head_y): (i32, i32) = self.head_position();
 
         let mut moving_dir = self.direction;
@@ -226,12 +610,8 @@ impl Snake {
             moving_dir = d
         }
 
-        match moving_dir {
-            Direction::Up => (head_x, head_y - 1),
-            Direction::Down => (head_x, head_y + 1),
-            Direction::Left => (head_x - 1, head_y),
-            Direction::Right => (head_x + 1, head_y),
-        }
+        let cell = topology.neighbour(head_x, head_y, moving_dir, width, height);
+        teleport(portals, cell)
     }
 
     /// Restores the snake's tail, effectively growing the snake by one block.
@@ -243,7 +623,7 @@ impl Snake {
     ///
     /// ```rust
     /// let mut snake = Snake::new(2, 2);
-    /// snake.move_forward(Some(Direction::Up));
+    /// snake.move_forward(Some(Direction::Down), Grid::Square.topology(), 20, 20, &[]);
     /// snake.restore_tail();
     /// assert_eq!(snake.body.len(), 4);
     /// ```
@@ -252,6 +632,33 @@ impl Snake {
         self.body.push_back(blk);
     }
 
+    /// Removes the last block of the snake's body, shrinking the snake by one block.
+    ///
+    /// The removed block is stored in `tail`, so the tail is drawn sliding out of it.
+    /// The head is never removed.
+    ///
+    /// # Returns
+    ///
+    /// The position of the removed block, or `None` if the snake is only a head.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut snake = Snake::new(2, 2);
+    /// assert_eq!(snake.trim_tail(), Some((2, 2)));
+    /// assert_eq!(snake.len(), 2);
+    /// ```
+    pub fn trim_tail(&mut self) -> Option<(i32, i32)> {
+        if self.body.len() < 2 {
+            return None;
+        }
+
+        let blk = self.body.pop_back().unwrap();
+        let position = (blk.x, blk.y);
+        self.tail = Some(blk);
+        Some(position)
+    }
+
     /// Checks if the given coordinates overlap with the snake's body, excluding the head.
     ///
     /// # Arguments
@@ -274,17 +681,130 @@ impl Snake {
     /// assert!(!snake.overlap_tail(0, 0)); // false because no block is at (0, 0)
     /// ```
     pub fn overlap_tail(&self, x: i32, y: i32) -> bool {
-        let mut ch = 0;
-        for block in &self.body {
-            if x == block.x && y == block.y {
-                return true;
+        self.overlap_index(x, y).is_some()
+    }
+
+    /// Checks that the snake is a valid body inside the walls of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `topology` - The `Topology` of the board.
+    /// * `portals` - The portals on the board.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the snake has at least two blocks, every block lies inside the walls,
+    /// each block is next to the one before it or linked to it through a portal, no two
+    /// blocks share a cell, the head faces one of the directions of the board away from
+    /// the rest of the body and the stored tail block is where the tail just was.
+    /// Otherwise a message describing the first problem found.
+    ///
+    /// This is used to reject snakes that could not come out of a real game, such as
+    /// those from a corrupted or hand-edited save.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let snake = Snake::new(2, 2);
+    /// assert!(snake.validate(20, 20, Grid::Square.topology(), &[]).is_ok());
+    /// assert!(snake.validate(4, 4, Grid::Square.topology(), &[]).is_err());
+    /// ```
+    pub fn validate(
+        &self,
+        width: i32,
+        height: i32,
+        topology: &dyn Topology,
+        portals: &[Portal],
+    ) -> Result<(), String> {
+        if self.body.len() < 2 {
+            return Err(String::from("the snake needs at least two blocks"));
+        }
+
+        for (index, block) in self.body.iter().enumerate() {
+            if block.x <= 0 || block.y <= 0 || block.x >= width - 1 || block.y >= height - 1 {
+                return Err(format!(
+                    "segment {} at ({}, {}) is outside the board",
+                    index, block.x, block.y
+                ));
+            }
+            if self.body.iter().skip(index + 1).any(|other| other == block) {
+                return Err(format!("segment {} overlaps another segment", index));
             }
+        }
+
+        let blocks: Vec<&Block> = self.body.iter().collect();
+        for (index, pair) in blocks.windows(2).enumerate() {
+            if pair[1]
+                .neighbour_direction(pair[0], topology, width, height, portals)
+                .is_none()
+            {
+                return Err(format!(
+                    "segments {} and {} are not adjacent",
+                    index,
+                    index + 1
+                ));
+            }
+        }
+
+        if blocks[1].neighbour_direction(blocks[0], topology, width, height, portals)
+            != Some(self.direction)
+        {
+            return Err(format!("the head does not face {}", self.direction.name()));
+        }
 
-            ch += 1;
-            if ch == self.body.len() - 1 {
-                break;
+        let last = blocks[blocks.len() - 1];
+        if let Some(tail) = &self.tail {
+            if tail != last
+                && tail
+                    .neighbour_direction(last, topology, width, height, portals)
+                    .is_none()
+            {
+                return Err(String::from(
+                    "the removed tail block is not next to the tail",
+                ));
             }
         }
-        false
+
+        Ok(())
+    }
+
+    /// Checks if any block of the snake's body, including the last one, is at the given
+    /// coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate to check.
+    /// * `y` - The y-coordinate to check.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.body.iter().any(|block| block.x == x && block.y == y)
+    }
+
+    /// Finds which block of the snake's body is at the given coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate to check for overlap.
+    /// * `y` - The y-coordinate to check for overlap.
+    ///
+    /// # Returns
+    ///
+    /// The index of the overlapping block counted from the head, which is 0, or `None`
+    /// if there is no such block. Like `overlap_tail`, the last block is not checked
+    /// because it moves out of the way when the snake moves.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let snake = Snake::new(2, 2);
+    /// assert_eq!(snake.overlap_index(3, 2), Some(1));
+    /// assert_eq!(snake.overlap_index(0, 0), None);
+    /// ```
+    pub fn overlap_index(&self, x: i32, y: i32) -> Option<usize> {
+        self.body
+            .iter()
+            .take(self.body.len() - 1)
+            .position(|block| x == block.x && y == block.y)
     }
 }
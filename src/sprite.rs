@@ -0,0 +1,199 @@
+use crate::draw::BLOCK_SIZE;
+use crate::snake::{Direction, Segment, SegmentKind};
+use crate::storage::data_dir;
+use crate::theme::Theme;
+use piston_window::math::Matrix2d;
+use piston_window::{
+    ellipse, polygon, rectangle, Context, Flip, G2d, G2dTexture, G2dTextureContext, Image,
+    ImageSize, Texture, TextureSettings, Transformed,
+};
+
+const SPRITES_FILE: &str = "sprites/snake.png";
+const SPRITE_COUNT: f64 = 4.0;
+
+/// Half of the thickness of the body, as a fraction of a block.
+const BODY_HALF_WIDTH: f64 = 0.35;
+
+/// A sprite sheet with one tile for each kind of snake segment.
+///
+/// The sheet is a single row of four square tiles: the head facing right, a
+/// horizontal straight piece, a corner joining the right and bottom edges, and
+/// the tail with the rest of the body to its right. The tiles are rotated to
+/// match the segments of the snake.
+pub struct SnakeSprites {
+    texture: G2dTexture,
+    tile: f64,
+}
+
+impl SnakeSprites {
+    /// Loads the snake sprite sheet from the data directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The texture context of the window.
+    ///
+    /// # Returns
+    ///
+    /// The loaded `SnakeSprites`, or `None` if `sprites/snake.png` does not exist or
+    /// cannot be decoded. A sheet that is not four tiles wide is rejected as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// // Assuming you have a valid PistonWindow:
+    /// // let sprites = SnakeSprites::load(&mut window.create_texture_context());
+    /// ```
+    pub fn load(context: &mut G2dTextureContext) -> Option<SnakeSprites> {
+        let path = data_dir().join(SPRITES_FILE);
+        if !path.exists() {
+            return None;
+        }
+
+        let texture = match Texture::from_path(context, &path, Flip::None, &TextureSettings::new())
+        {
+            Ok(texture) => texture,
+            Err(e) => {
+                eprintln!("failed to load {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        let (width, height) = texture.get_size();
+        if width != height * SPRITE_COUNT as u32 {
+            eprintln!("{} must be four square tiles wide", path.display());
+            return None;
+        }
+
+        Some(SnakeSprites {
+            texture,
+            tile: height as f64,
+        })
+    }
+
+    /// Draws one segment of the snake with the matching sprite.
+    ///
+    /// # Arguments
+    ///
+    /// * `segment` - The `Segment` to draw.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    pub fn draw_segment(&self, segment: &Segment, con: &Context, g: &mut G2d) {
+        let (index, transform) = segment_transform(segment, con);
+        let half = BLOCK_SIZE / 2.0;
+
+        Image::new()
+            .src_rect([index as f64 * self.tile, 0.0, self.tile, self.tile])
+            .rect([-half, -half, half * 2.0, half * 2.0])
+            .draw(&self.texture, &con.draw_state, transform, g);
+    }
+}
+
+/// Draws one segment of the snake as a procedural shape.
+///
+/// # Arguments
+///
+/// * `segment` - The `Segment` to draw.
+/// * `theme` - The `Theme` providing the colors of the snake.
+/// * `con` - A reference to the `Context` for rendering.
+/// * `g` - A mutable reference to the `G2d` graphics backend.
+///
+/// The head is rounded at the front and has eyes, straight pieces and corners
+/// are slightly narrower than a block so the turns of the body stay visible,
+/// and the tail tapers to a point.
+///
+/// # Example
+///
+/// ```rust
+/// // Assuming you have a valid Context and G2d instance:
+/// // for segment in snake.segments() {
+/// //     draw_segment_shape(&segment, &Theme::classic(), &con, &mut g);
+/// // }
+/// ```
+pub fn draw_segment_shape(segment: &Segment, theme: &Theme, con: &Context, g: &mut G2d) {
+    let (_, transform) = segment_transform(segment, con);
+    let size = BLOCK_SIZE;
+    let half = size / 2.0;
+    let body = size * BODY_HALF_WIDTH;
+
+    match segment.kind {
+        SegmentKind::Head(_) => {
+            rectangle(theme.snake, [-half, -body, half, body * 2.0], transform, g);
+            ellipse(theme.snake, [-half, -half, size, size], transform, g);
+
+            let eye = size * 0.14;
+            for eye_y in [-size * 0.22, size * 0.22 - eye] {
+                rectangle(
+                    theme.snake_eyes,
+                    [size * 0.1, eye_y, eye, eye],
+                    transform,
+                    g,
+                );
+            }
+        }
+        SegmentKind::Straight(_) => {
+            rectangle(theme.snake, [-half, -body, size, body * 2.0], transform, g);
+        }
+        SegmentKind::Corner(_, _) => {
+            rectangle(
+                theme.snake,
+                [-body, -body, half + body, body * 2.0],
+                transform,
+                g,
+            );
+            rectangle(
+                theme.snake,
+                [-body, -body, body * 2.0, half + body],
+                transform,
+                g,
+            );
+        }
+        SegmentKind::Tail(_) => {
+            let tip = [-half * 0.8, 0.0];
+            polygon(
+                theme.snake,
+                &[[half, -body], [half, body], tip],
+                transform,
+                g,
+            );
+        }
+    }
+}
+
+/// Computes which tile a segment uses and how it is placed on the board.
+///
+/// # Arguments
+///
+/// * `segment` - The `Segment` to place.
+/// * `con` - A reference to the `Context` for rendering.
+///
+/// # Returns
+///
+/// The index of the tile in the sprite sheet, and a transform whose origin is the
+/// center of the segment's block, rotated so that the base orientation of the tile
+/// matches the segment.
+fn segment_transform(segment: &Segment, con: &Context) -> (usize, Matrix2d) {
+    let (index, angle) = match segment.kind {
+        SegmentKind::Head(dir) => (0, dir.angle()),
+        SegmentKind::Straight(dir) => (1, dir.angle()),
+        SegmentKind::Corner(a, b) => (2, corner_angle(a, b)),
+        SegmentKind::Tail(dir) => (3, dir.angle()),
+    };
+
+    let half = BLOCK_SIZE / 2.0;
+    let transform = con
+        .transform
+        .trans(segment.x * BLOCK_SIZE + half, segment.y * BLOCK_SIZE + half)
+        .rot_deg(angle);
+
+    (index, transform)
+}
+
+/// Returns the rotation that turns the base corner, which joins the right and
+/// bottom edges, into a corner joining the edges in directions `a` and `b`.
+fn corner_angle(a: Direction, b: Direction) -> f64 {
+    if (b.angle() - a.angle()).rem_euclid(360.0) == 90.0 {
+        a.angle()
+    } else {
+        b.angle()
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+/// The screens the game can be on.
+///
+/// Menu screens carry the index of the currently selected item, and the rewind
+/// screen of the practice mode how many ticks back the shown snapshot is. The campaign
+/// screen shows the progress through the campaign, and the level complete screen
+/// follows reaching the goal of a campaign level.
+#[derive(Copy, Clone, PartialEq)]
+pub enum GameState {
+    Title { selected: usize },
+    Levels { selected: usize },
+    Campaign { selected: usize },
+    Playing,
+    Paused,
+    GameOver,
+    LevelComplete,
+    Rewinding { steps_back: usize },
+    Settings { selected: usize },
+    HighScores,
+    Stats,
+    Editor,
+}
+
+/// The kind of game being played.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlayMode {
+    /// A regular game that counts for the high scores.
+    Normal,
+    /// A game that can be rewound after dying and does not count for anything.
+    Practice,
+    /// The daily challenge of the given day, counted in days since 1970-01-01.
+    Daily { day: i64 },
+    /// A campaign level, given by its zero-based index, which counts for the statistics
+    /// and achievements but not for the high scores.
+    Campaign { level: usize },
+}
+
+/// The entries of the title screen menu.
+#[derive(Copy, Clone, PartialEq)]
+pub enum TitleItem {
+    Play,
+    Daily,
+    Campaign,
+    Levels,
+    Resume,
+    Practice,
+    Settings,
+    Stats,
+    HighScores,
+    Quit,
+}
+
+impl TitleItem {
+    /// All title menu entries, in display order.
+    pub const ALL: [TitleItem; 10] = [
+        TitleItem::Play,
+        TitleItem::Daily,
+        TitleItem::Campaign,
+        TitleItem::Levels,
+        TitleItem::Resume,
+        TitleItem::Practice,
+        TitleItem::Settings,
+        TitleItem::Stats,
+        TitleItem::HighScores,
+        TitleItem::Quit,
+    ];
+
+    /// Returns the text shown for the entry.
+    pub fn label(&self) -> &'static str {
+        match *self {
+            TitleItem::Play => "PLAY",
+            TitleItem::Daily => "DAILY CHALLENGE",
+            TitleItem::Campaign => "CAMPAIGN",
+            TitleItem::Levels => "LEVELS",
+            TitleItem::Resume => "RESUME",
+            TitleItem::Practice => "PRACTICE",
+            TitleItem::Settings => "SETTINGS",
+            TitleItem::Stats => "STATISTICS",
+            TitleItem::HighScores => "HIGH SCORES",
+            TitleItem::Quit => "QUIT",
+        }
+    }
+
+    /// Returns the position of the entry in the menu.
+    pub fn index(&self) -> usize {
+        TitleItem::ALL.iter().position(|item| item == self).unwrap()
+    }
+}
+
+/// The entries of the settings screen menu.
+#[derive(Copy, Clone, PartialEq)]
+pub enum SettingsItem {
+    Speed,
+    Theme,
+    SnakeStyle,
+    EffectsVolume,
+    MusicVolume,
+    Mute,
+    SaveFormat,
+    Grid,
+    Hunger,
+    Back,
+}
+
+impl SettingsItem {
+    /// All settings menu entries, in display order.
+    pub const ALL: [SettingsItem; 10] = [
+        SettingsItem::Speed,
+        SettingsItem::Theme,
+        SettingsItem::SnakeStyle,
+        SettingsItem::EffectsVolume,
+        SettingsItem::MusicVolume,
+        SettingsItem::Mute,
+        SettingsItem::SaveFormat,
+        SettingsItem::Grid,
+        SettingsItem::Hunger,
+        SettingsItem::Back,
+    ];
+}
+
+/// Moves a menu selection up or down, wrapping around at both ends.
+///
+/// # Arguments
+///
+/// * `selected` - The index of the currently selected item.
+/// * `count` - The number of items in the menu.
+/// * `down` - `true` to move to the next item, `false` to move to the previous one.
+///
+/// # Returns
+///
+/// The index of the newly selected item.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(move_selection(0, 4, false), 3);
+/// assert_eq!(move_selection(3, 4, true), 0);
+/// ```
+pub fn move_selection(selected: usize, count: usize, down: bool) -> usize {
+    if down {
+        (selected + 1) % count
+    } else {
+        (selected + count - 1) % count
+    }
+}
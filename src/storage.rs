@@ -0,0 +1,126 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const DATA_DIR_VAR: &str = "SNAKEY_DATA_DIR";
+const DATA_DIR_NAME: &str = ".snakey-rust";
+
+/// Returns the directory where the game keeps its local data files.
+///
+/// # Returns
+///
+/// A `PathBuf` pointing to the data directory. The directory is not created.
+///
+/// The `SNAKEY_DATA_DIR` environment variable takes precedence. Otherwise the
+/// data lives in `.snakey-rust` inside the user's home directory, falling back
+/// to the current working directory if no home directory is known.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os(DATA_DIR_VAR) {
+        return PathBuf::from(dir);
+    }
+
+    match env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+        Some(home) => PathBuf::from(home).join(DATA_DIR_NAME),
+        None => PathBuf::from(DATA_DIR_NAME),
+    }
+}
+
+/// Reads a data file as a string.
+///
+/// # Arguments
+///
+/// * `name` - The file name, relative to the data directory.
+///
+/// # Returns
+///
+/// The contents of the file, or `None` if it does not exist or cannot be read.
+///
+/// # Example
+///
+/// ```rust
+/// let text = read_data_file("highscores.txt").unwrap_or_default();
+/// ```
+pub fn read_data_file(name: &str) -> Option<String> {
+    fs::read_to_string(data_dir().join(name)).ok()
+}
+
+/// Reads a data file as raw bytes.
+///
+/// # Arguments
+///
+/// * `name` - The file name, relative to the data directory.
+///
+/// # Returns
+///
+/// The contents of the file, or `None` if it does not exist or cannot be read.
+pub fn read_data_bytes(name: &str) -> Option<Vec<u8>> {
+    fs::read(data_dir().join(name)).ok()
+}
+
+/// Writes a string or bytes to a data file, creating the directories it is in if needed.
+///
+/// # Arguments
+///
+/// * `name` - The file name, relative to the data directory. It may include
+///   subdirectories.
+/// * `contents` - The text or bytes to write.
+///
+/// # Returns
+///
+/// An `io::Result` that is an error if the directory or file could not be written.
+///
+/// # Example
+///
+/// ```rust
+/// write_data_file("highscores.txt", "12\n").unwrap();
+/// ```
+pub fn write_data_file(name: &str, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = data_dir().join(name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Removes a data file.
+///
+/// # Arguments
+///
+/// * `name` - The file name, relative to the data directory.
+///
+/// # Returns
+///
+/// An `io::Result` that is an error if the file exists but could not be removed.
+/// A file that does not exist is not an error.
+pub fn remove_data_file(name: &str) -> io::Result<()> {
+    match fs::remove_file(data_dir().join(name)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Iterates over the `key=value` pairs of a settings-style text file.
+///
+/// # Arguments
+///
+/// * `text` - The contents of the file.
+///
+/// # Returns
+///
+/// An iterator over `(key, value)` pairs with surrounding whitespace removed.
+/// Empty lines, lines starting with `#` and lines without `=` are skipped.
+///
+/// # Example
+///
+/// ```rust
+/// let pairs: Vec<_> = key_values("# comment\nspeed = FAST\n").collect();
+/// assert_eq!(pairs, vec![("speed", "FAST")]);
+/// ```
+pub fn key_values(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+}
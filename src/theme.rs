@@ -0,0 +1,235 @@
+use crate::storage::{data_dir, key_values};
+use piston_window::types::Color;
+use std::fs;
+
+const THEMES_DIR: &str = "themes";
+const THEME_EXTENSION: &str = "theme";
+
+/// The colors portals are drawn in, one per pair in the order the pairs are listed,
+/// starting over after the last one. They are the same in every theme.
+pub const PORTAL_COLORS: [Color; 4] = [
+    [0.85, 0.30, 0.95, 1.0],
+    [0.20, 0.85, 0.95, 1.0],
+    [1.00, 0.60, 0.10, 1.0],
+    [0.55, 0.95, 0.25, 1.0],
+];
+
+/// The colors used to draw every part of the game.
+#[derive(Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub letterbox: Color,
+    pub border: Color,
+    pub snake: Color,
+    pub snake_eyes: Color,
+    pub food: Color,
+    pub enemy: Color,
+    pub game_over: Color,
+    pub highlight: Color,
+    pub text: Color,
+    pub overlay: Color,
+    pub title: Color,
+    pub menu_item: Color,
+    pub menu_selected: Color,
+}
+
+impl Theme {
+    /// The original gray and green look of the game.
+    pub fn classic() -> Theme {
+        Theme {
+            name: String::from("CLASSIC"),
+            background: [0.50, 0.50, 0.50, 1.0],
+            letterbox: [0.10, 0.10, 0.10, 1.0],
+            border: [0.00, 0.00, 0.00, 1.0],
+            snake: [0.00, 0.80, 0.00, 1.0],
+            snake_eyes: [0.00, 0.00, 0.00, 1.0],
+            food: [0.80, 0.00, 0.00, 1.0],
+            enemy: [0.55, 0.00, 0.55, 1.0],
+            game_over: [0.90, 0.00, 0.00, 0.5],
+            highlight: [1.00, 0.85, 0.00, 1.0],
+            text: [1.00, 1.00, 1.00, 1.0],
+            overlay: [0.00, 0.00, 0.00, 0.7],
+            title: [0.00, 0.80, 0.00, 1.0],
+            menu_item: [1.00, 1.00, 1.00, 1.0],
+            menu_selected: [1.00, 0.85, 0.00, 1.0],
+        }
+    }
+
+    /// Pure black and white with saturated accents, for low vision players.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            name: String::from("HIGH CONTRAST"),
+            background: [0.00, 0.00, 0.00, 1.0],
+            letterbox: [0.00, 0.00, 0.00, 1.0],
+            border: [1.00, 1.00, 1.00, 1.0],
+            snake: [1.00, 1.00, 0.00, 1.0],
+            snake_eyes: [0.00, 0.00, 0.00, 1.0],
+            food: [0.00, 1.00, 1.00, 1.0],
+            enemy: [1.00, 0.00, 0.00, 1.0],
+            game_over: [0.00, 0.00, 0.00, 0.8],
+            highlight: [1.00, 0.00, 1.00, 1.0],
+            text: [1.00, 1.00, 1.00, 1.0],
+            overlay: [0.00, 0.00, 0.00, 0.9],
+            title: [1.00, 1.00, 0.00, 1.0],
+            menu_item: [1.00, 1.00, 1.00, 1.0],
+            menu_selected: [0.00, 1.00, 1.00, 1.0],
+        }
+    }
+
+    /// Blue and orange from the Okabe-Ito palette, safe for red-green
+    /// deficiencies of the green cones.
+    pub fn deuteranopia() -> Theme {
+        Theme {
+            name: String::from("DEUTERANOPIA"),
+            background: [0.85, 0.85, 0.85, 1.0],
+            letterbox: [0.20, 0.20, 0.20, 1.0],
+            border: [0.00, 0.00, 0.00, 1.0],
+            snake: [0.00, 0.45, 0.70, 1.0],
+            snake_eyes: [1.00, 1.00, 1.00, 1.0],
+            food: [0.90, 0.60, 0.00, 1.0],
+            enemy: [0.84, 0.37, 0.00, 1.0],
+            game_over: [0.00, 0.00, 0.00, 0.6],
+            highlight: [0.80, 0.47, 0.65, 1.0],
+            text: [1.00, 1.00, 1.00, 1.0],
+            overlay: [0.00, 0.00, 0.00, 0.75],
+            title: [0.34, 0.71, 0.91, 1.0],
+            menu_item: [1.00, 1.00, 1.00, 1.0],
+            menu_selected: [0.90, 0.60, 0.00, 1.0],
+        }
+    }
+
+    /// Sky blue and yellow from the Okabe-Ito palette on a dark board, safe for
+    /// red-green deficiencies of the red cones, which also darken reds.
+    pub fn protanopia() -> Theme {
+        Theme {
+            name: String::from("PROTANOPIA"),
+            background: [0.15, 0.15, 0.15, 1.0],
+            letterbox: [0.05, 0.05, 0.05, 1.0],
+            border: [0.60, 0.60, 0.60, 1.0],
+            snake: [0.34, 0.71, 0.91, 1.0],
+            snake_eyes: [0.00, 0.00, 0.00, 1.0],
+            food: [0.94, 0.89, 0.26, 1.0],
+            enemy: [0.00, 0.62, 0.45, 1.0],
+            game_over: [0.00, 0.00, 0.00, 0.6],
+            highlight: [0.80, 0.47, 0.65, 1.0],
+            text: [1.00, 1.00, 1.00, 1.0],
+            overlay: [0.00, 0.00, 0.00, 0.75],
+            title: [0.34, 0.71, 0.91, 1.0],
+            menu_item: [1.00, 1.00, 1.00, 1.0],
+            menu_selected: [0.94, 0.89, 0.26, 1.0],
+        }
+    }
+
+    /// Parses a theme file.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The contents of the theme file.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `Theme`, or `None` if the file has no `name`. Colors that are
+    /// missing or invalid keep their value from the classic theme.
+    ///
+    /// The file holds one `key=value` pair per line, where the key is a field of
+    /// `Theme` and the value is a color written as `#RRGGBB` or `#RRGGBBAA`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let theme = Theme::parse("name=ocean\nbackground=#003366\nsnake=#66CCFF").unwrap();
+    /// assert_eq!(theme.name, "OCEAN");
+    /// ```
+    pub fn parse(text: &str) -> Option<Theme> {
+        let mut theme = Theme::classic();
+        let mut name = None;
+
+        for (key, value) in key_values(text) {
+            if key == "name" {
+                name = Some(value.to_ascii_uppercase());
+                continue;
+            }
+
+            let Some(color) = parse_color(value) else {
+                continue;
+            };
+
+            match key {
+                "background" => theme.background = color,
+                "letterbox" => theme.letterbox = color,
+                "border" => theme.border = color,
+                "snake" => theme.snake = color,
+                "snake_eyes" => theme.snake_eyes = color,
+                "food" => theme.food = color,
+                "enemy" => theme.enemy = color,
+                "game_over" => theme.game_over = color,
+                "highlight" => theme.highlight = color,
+                "text" => theme.text = color,
+                "overlay" => theme.overlay = color,
+                "title" => theme.title = color,
+                "menu_item" => theme.menu_item = color,
+                "menu_selected" => theme.menu_selected = color,
+                _ => {}
+            }
+        }
+
+        theme.name = name?;
+        Some(theme)
+    }
+}
+
+/// Returns the built-in themes followed by the themes found on disk.
+///
+/// # Returns
+///
+/// A non-empty `Vec<Theme>` whose first entry is the classic theme.
+///
+/// Theme files are read from the `themes` folder of the data directory and
+/// must have the `.theme` extension. Files that cannot be read or have no name
+/// are skipped.
+pub fn load_themes() -> Vec<Theme> {
+    let mut themes = vec![
+        Theme::classic(),
+        Theme::high_contrast(),
+        Theme::deuteranopia(),
+        Theme::protanopia(),
+    ];
+
+    let Ok(entries) = fs::read_dir(data_dir().join(THEMES_DIR)) else {
+        return themes;
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == THEME_EXTENSION))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        if let Some(theme) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|t| Theme::parse(&t))
+        {
+            themes.push(theme);
+        }
+    }
+
+    themes
+}
+
+/// Parses a color written as `#RRGGBB` or `#RRGGBBAA`.
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut color = [1.0; 4];
+    for (index, channel) in color.iter_mut().enumerate().take(hex.len() / 2) {
+        let byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).ok()?;
+        *channel = byte as f32 / 255.0;
+    }
+
+    Some(color)
+}
@@ -0,0 +1,159 @@
+/// The longest frame, in seconds, that the game catches up on. A longer hitch, for
+/// example while the window is being dragged, only counts for this much.
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// The most logic ticks that are run in a single frame.
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+/// Turns the variable time between frames into a whole number of logic ticks, keeping
+/// the time left over for the next frame.
+///
+/// The game therefore moves at the same rate whatever the frame rate, and a slow frame
+/// is made up for with several ticks. Ticks are taken out one at a time, each with the
+/// length it has when it is taken, so a tick that changes the pace of the game also
+/// changes how long the rest of the frame's ticks are. To avoid a "spiral of death",
+/// where catching up takes so long that the game falls further and further behind, both
+/// the time a single frame counts for and the number of ticks it runs are capped; time
+/// beyond that is dropped.
+#[derive(Default)]
+pub struct FixedTimestep {
+    accumulated: f64,
+    /// The number of ticks taken out since the last frame was added.
+    frame_ticks: u32,
+}
+
+impl FixedTimestep {
+    /// Creates a timestep that already has some time left over.
+    ///
+    /// # Arguments
+    ///
+    /// * `accumulated` - The time in seconds left over from earlier frames.
+    pub fn with_accumulated(accumulated: f64) -> FixedTimestep {
+        FixedTimestep {
+            accumulated,
+            frame_ticks: 0,
+        }
+    }
+
+    /// Returns the time in seconds left over from earlier frames, which is less than
+    /// one tick.
+    pub fn accumulated(&self) -> f64 {
+        self.accumulated
+    }
+
+    /// Adds the time of a new frame, whose ticks are then taken out with `next_tick`.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta_time` - The time in seconds elapsed since the last frame.
+    pub fn add_frame(&mut self, delta_time: f64) {
+        self.accumulated += delta_time.clamp(0.0, MAX_FRAME_TIME);
+        self.frame_ticks = 0;
+    }
+
+    /// Takes out the next tick of the current frame, if the time added covers it.
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - The length of the tick in seconds.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a logic tick is to be run now. Once the frame has run
+    /// `MAX_TICKS_PER_FRAME` ticks, any whole ticks still due are dropped and `false` is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut timestep = FixedTimestep::default();
+    /// timestep.add_frame(0.25);
+    /// assert!(timestep.next_tick(0.1));
+    /// assert!(timestep.next_tick(0.1));
+    /// assert!(!timestep.next_tick(0.1));
+    /// assert!((timestep.accumulated() - 0.05).abs() < 1e-9);
+    /// ```
+    pub fn next_tick(&mut self, period: f64) -> bool {
+        if self.accumulated < period {
+            return false;
+        }
+        if self.frame_ticks == MAX_TICKS_PER_FRAME {
+            self.accumulated %= period;
+            return false;
+        }
+
+        self.accumulated -= period;
+        self.frame_ticks += 1;
+        true
+    }
+
+    /// Returns how far the next tick is, as a fraction of the tick length.
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - The length of a logic tick in seconds.
+    ///
+    /// # Returns
+    ///
+    /// A number from 0 right after a tick to 1 when the next one is due, used to
+    /// interpolate drawing between ticks.
+    pub fn progress(&self, period: f64) -> f64 {
+        (self.accumulated / period).min(1.0)
+    }
+
+    /// Drops the time left over, so the next tick is a full tick away.
+    pub fn reset(&mut self) {
+        self.accumulated = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds a frame and takes out its ticks, each with the period given for it.
+    fn ticks(timestep: &mut FixedTimestep, delta_time: f64, periods: &[f64]) -> u32 {
+        timestep.add_frame(delta_time);
+        let mut ticks = 0;
+        while timestep.next_tick(periods[(ticks as usize).min(periods.len() - 1)]) {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn leftover_time_carries_over() {
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(ticks(&mut timestep, 0.15, &[0.1]), 1);
+        assert!((timestep.accumulated() - 0.05).abs() < 1e-9);
+        assert_eq!(ticks(&mut timestep, 0.06, &[0.1]), 1);
+        assert!((timestep.progress(0.1) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn long_frames_count_for_at_most_max_frame_time() {
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(ticks(&mut timestep, 10.0, &[0.1]), 2);
+        assert!((timestep.accumulated() - (MAX_FRAME_TIME - 0.2)).abs() < 1e-9);
+        assert_eq!(ticks(&mut timestep, -1.0, &[0.1]), 0);
+    }
+
+    #[test]
+    fn ticks_beyond_max_ticks_per_frame_are_dropped() {
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(ticks(&mut timestep, 0.2, &[0.03]), MAX_TICKS_PER_FRAME);
+        assert!((timestep.accumulated() - 0.02).abs() < 1e-9);
+        assert_eq!(ticks(&mut timestep, 0.0, &[0.03]), 0);
+    }
+
+    #[test]
+    fn a_period_change_applies_to_the_rest_of_the_frame() {
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(ticks(&mut timestep, 0.2, &[0.05, 0.05, 0.1]), 3);
+        assert!(timestep.accumulated().abs() < 1e-9);
+
+        let mut timestep = FixedTimestep::default();
+        assert_eq!(ticks(&mut timestep, 0.2, &[0.1, 0.05]), 3);
+        assert!(timestep.accumulated().abs() < 1e-9);
+    }
+}
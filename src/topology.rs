@@ -0,0 +1,432 @@
+use crate::draw::{draw_block_at, draw_block_outline_at, draw_hex_at, draw_hex_outline_at};
+use crate::snake::Direction;
+use piston_window::types::Color;
+use piston_window::{Context, G2d};
+use serde::{Deserialize, Serialize};
+
+/// The shape of the board: which cells are next to each other, and how the cells are
+/// laid out and drawn on screen.
+///
+/// Cells are always addressed by integer `(x, y)` coordinates with the walls on the
+/// outermost rows and columns, so the rules of the game work the same on every board.
+/// Positions on screen are measured in blocks, and each cell fits in a block-sized box
+/// whose top-left corner is returned by `position`.
+pub trait Topology {
+    /// Returns the directions the snake can move in.
+    fn directions(&self) -> &'static [Direction];
+
+    /// Returns the cell one step away in a direction, on a board without walls.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell to step from.
+    /// * `y` - The y-coordinate of the cell to step from.
+    /// * `dir` - The `Direction` to step in.
+    fn step(&self, x: i32, y: i32, dir: Direction) -> (i32, i32);
+
+    /// Returns the cell that is entered when stepping onto a cell, which is the cell
+    /// itself unless the board wraps around at the walls.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell stepped onto.
+    /// * `y` - The y-coordinate of the cell stepped onto.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    fn wrap(&self, x: i32, y: i32, _width: i32, _height: i32) -> (i32, i32) {
+        (x, y)
+    }
+
+    /// Returns the top-left corner on screen of the box a cell is drawn in, in blocks.
+    fn position(&self, x: i32, y: i32) -> (f64, f64) {
+        (x as f64, y as f64)
+    }
+
+    /// Returns the cell drawn at a position on screen, the inverse of `position`.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the position, in blocks.
+    /// * `y` - The y-coordinate of the position, in blocks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// assert_eq!(Grid::Square.topology().cell_at(2.5, 3.9), (2, 3));
+    /// assert_eq!(Grid::Hex.topology().cell_at(2.1, 3.5), (1, 3));
+    /// ```
+    fn cell_at(&self, x: f64, y: f64) -> (i32, i32) {
+        (x.floor() as i32, y.floor() as i32)
+    }
+
+    /// Turns an arrow key into a direction of the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The `Direction` the snake is moving in.
+    /// * `pressed` - The `Direction` of the key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// The direction to move in, or `None` if the key has no meaning on this board.
+    fn steer(&self, _current: Direction, pressed: Direction) -> Option<Direction> {
+        self.directions().contains(&pressed).then_some(pressed)
+    }
+
+    /// Returns whether the cells are squares, which the shaped and sprite snake
+    /// styles are drawn for.
+    fn square_cells(&self) -> bool {
+        true
+    }
+
+    /// Fills the cell whose box has its top-left corner at a position on screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The `Color` of the cell.
+    /// * `x` - The x-coordinate of the position, in blocks.
+    /// * `y` - The y-coordinate of the position, in blocks.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    fn fill(&self, color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+        draw_block_at(color, x, y, con, g);
+    }
+
+    /// Outlines the cell whose box has its top-left corner at a position on screen.
+    ///
+    /// The arguments are the same as for `fill`.
+    fn outline(&self, color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+        draw_block_outline_at(color, x, y, con, g);
+    }
+
+    /// Returns the cell the snake enters when moving from a cell in a direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell to move from.
+    /// * `y` - The y-coordinate of the cell to move from.
+    /// * `dir` - The `Direction` to move in.
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// assert_eq!(Grid::Square.topology().neighbour(1, 1, Direction::Left, 20, 20), (0, 1));
+    /// assert_eq!(Grid::Wrap.topology().neighbour(1, 1, Direction::Left, 20, 20), (18, 1));
+    /// ```
+    fn neighbour(&self, x: i32, y: i32, dir: Direction, width: i32, height: i32) -> (i32, i32) {
+        let (x, y) = self.step(x, y, dir);
+        self.wrap(x, y, width, height)
+    }
+
+    /// Returns the direction in which a cell lies from another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The cell to look from.
+    /// * `to` - The cell to look at.
+    ///
+    /// # Returns
+    ///
+    /// The `Direction` from `from` to `to`, or `None` if they are not direct neighbours
+    /// on a board without walls. Cells next to each other across the walls of a board
+    /// that wraps around are not counted, since they are not next to each other on screen.
+    fn direction_between(&self, from: (i32, i32), to: (i32, i32)) -> Option<Direction> {
+        self.directions()
+            .iter()
+            .copied()
+            .find(|&dir| self.step(from.0, from.1, dir) == to)
+    }
+
+    /// Fills a cell of the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The `Color` of the cell.
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    /// * `con` - A reference to the `Context` for rendering.
+    /// * `g` - A mutable reference to the `G2d` graphics backend.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// // Assuming you have a valid Context and G2d instance:
+    /// // Grid::Hex.topology().draw_cell([1.0, 0.0, 0.0, 1.0], 2, 3, &con, &mut g);
+    /// ```
+    fn draw_cell(&self, color: Color, x: i32, y: i32, con: &Context, g: &mut G2d) {
+        let (x, y) = self.position(x, y);
+        self.fill(color, x, y, con, g);
+    }
+
+    /// Outlines a cell of the board. The arguments are the same as for `draw_cell`.
+    fn draw_cell_outline(&self, color: Color, x: i32, y: i32, con: &Context, g: &mut G2d) {
+        let (x, y) = self.position(x, y);
+        self.outline(color, x, y, con, g);
+    }
+}
+
+/// Returns the cell one step away on a grid of squares.
+fn square_step(x: i32, y: i32, dir: Direction) -> (i32, i32) {
+    match dir {
+        Direction::Up => (x, y - 1),
+        Direction::Down => (x, y + 1),
+        Direction::Left => (x - 1, y),
+        Direction::Right => (x + 1, y),
+        Direction::UpLeft => (x - 1, y - 1),
+        Direction::UpRight => (x + 1, y - 1),
+        Direction::DownLeft => (x - 1, y + 1),
+        Direction::DownRight => (x + 1, y + 1),
+    }
+}
+
+/// The four directions of a grid of squares.
+const SQUARE_DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// The six directions of a grid of hexagons.
+const HEX_DIRECTIONS: [Direction; 6] = [
+    Direction::Left,
+    Direction::Right,
+    Direction::UpLeft,
+    Direction::UpRight,
+    Direction::DownLeft,
+    Direction::DownRight,
+];
+
+/// The classic board: square cells surrounded by walls.
+pub struct Square;
+
+impl Topology for Square {
+    fn directions(&self) -> &'static [Direction] {
+        &SQUARE_DIRECTIONS
+    }
+
+    fn step(&self, x: i32, y: i32, dir: Direction) -> (i32, i32) {
+        square_step(x, y, dir)
+    }
+}
+
+/// Square cells, but the snake leaving the board on one side comes back in on the
+/// other side instead of hitting the wall.
+pub struct SquareWrap;
+
+impl Topology for SquareWrap {
+    fn directions(&self) -> &'static [Direction] {
+        &SQUARE_DIRECTIONS
+    }
+
+    fn step(&self, x: i32, y: i32, dir: Direction) -> (i32, i32) {
+        square_step(x, y, dir)
+    }
+
+    fn wrap(&self, x: i32, y: i32, width: i32, height: i32) -> (i32, i32) {
+        (
+            (x - 1).rem_euclid(width - 2) + 1,
+            (y - 1).rem_euclid(height - 2) + 1,
+        )
+    }
+}
+
+/// Hexagonal cells with six directions of movement.
+///
+/// The hexagons point up and down, and every row is shifted by a quarter of a block,
+/// odd rows to the right and even rows to the left, so that each cell touches two
+/// cells in the row above and two in the row below. The up and down arrow keys go up
+/// or down on the side the snake is heading to.
+pub struct Hex;
+
+impl Hex {
+    /// Returns whether a row is shifted to the right.
+    fn is_odd_row(y: i32) -> bool {
+        y.rem_euclid(2) == 1
+    }
+}
+
+impl Topology for Hex {
+    fn directions(&self) -> &'static [Direction] {
+        &HEX_DIRECTIONS
+    }
+
+    fn step(&self, x: i32, y: i32, dir: Direction) -> (i32, i32) {
+        let shift = if Hex::is_odd_row(y) { 1 } else { 0 };
+        match dir {
+            Direction::UpLeft => (x - 1 + shift, y - 1),
+            Direction::UpRight => (x + shift, y - 1),
+            Direction::DownLeft => (x - 1 + shift, y + 1),
+            Direction::DownRight => (x + shift, y + 1),
+            _ => square_step(x, y, dir),
+        }
+    }
+
+    fn position(&self, x: i32, y: i32) -> (f64, f64) {
+        let shift = if Hex::is_odd_row(y) { 0.25 } else { -0.25 };
+        (x as f64 + shift, y as f64)
+    }
+
+    fn cell_at(&self, x: f64, y: f64) -> (i32, i32) {
+        let row = y.floor() as i32;
+        let (shift, _) = self.position(0, row);
+        ((x - shift).floor() as i32, row)
+    }
+
+    fn steer(&self, current: Direction, pressed: Direction) -> Option<Direction> {
+        let rightwards = matches!(
+            current,
+            Direction::Right | Direction::UpRight | Direction::DownRight
+        );
+        match (pressed, rightwards) {
+            (Direction::Up, true) => Some(Direction::UpRight),
+            (Direction::Up, false) => Some(Direction::UpLeft),
+            (Direction::Down, true) => Some(Direction::DownRight),
+            (Direction::Down, false) => Some(Direction::DownLeft),
+            _ => Some(pressed),
+        }
+    }
+
+    fn square_cells(&self) -> bool {
+        false
+    }
+
+    fn fill(&self, color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+        draw_hex_at(color, x, y, con, g);
+    }
+
+    fn outline(&self, color: Color, x: f64, y: f64, con: &Context, g: &mut G2d) {
+        draw_hex_outline_at(color, x, y, con, g);
+    }
+}
+
+/// The boards the player can choose from.
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Grid {
+    Square,
+    Wrap,
+    Hex,
+}
+
+impl Grid {
+    /// All boards, in menu order.
+    pub const ALL: [Grid; 3] = [Grid::Square, Grid::Wrap, Grid::Hex];
+
+    /// Returns the `Topology` of the board.
+    pub fn topology(&self) -> &'static dyn Topology {
+        match *self {
+            Grid::Square => &Square,
+            Grid::Wrap => &SquareWrap,
+            Grid::Hex => &Hex,
+        }
+    }
+
+    /// Returns the next board, wrapping around after the last one.
+    pub fn next(&self) -> Grid {
+        Grid::ALL[(self.index() + 1) % Grid::ALL.len()]
+    }
+
+    /// Returns the previous board, wrapping around before the first one.
+    pub fn previous(&self) -> Grid {
+        Grid::ALL[(self.index() + Grid::ALL.len() - 1) % Grid::ALL.len()]
+    }
+
+    /// Returns the name of the board as shown in menus and stored on disk.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Grid::Square => "SQUARE",
+            Grid::Wrap => "WRAP",
+            Grid::Hex => "HEX",
+        }
+    }
+
+    /// Finds the board with the given name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Grid> {
+        Grid::ALL
+            .into_iter()
+            .find(|grid| grid.name().eq_ignore_ascii_case(name))
+    }
+
+    fn index(&self) -> usize {
+        Grid::ALL.iter().position(|grid| grid == self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_step_is_undone_by_the_opposite_step() {
+        for grid in Grid::ALL {
+            let topology = grid.topology();
+            for (x, y) in [(5, 4), (5, 5), (0, 0), (-3, -3)] {
+                for &dir in topology.directions() {
+                    let (nx, ny) = topology.step(x, y, dir);
+                    assert_eq!(topology.step(nx, ny, dir.opposite()), (x, y));
+                    assert!(topology.direction_between((x, y), (nx, ny)) == Some(dir));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hex_neighbours_touch_on_screen() {
+        let topology = Grid::Hex.topology();
+        for (x, y) in [(5, 4), (5, 5)] {
+            let (cx, cy) = topology.position(x, y);
+            let mut neighbours: Vec<(i32, i32)> = topology
+                .directions()
+                .iter()
+                .map(|&dir| topology.step(x, y, dir))
+                .collect();
+            for &(nx, ny) in &neighbours {
+                let (px, py) = topology.position(nx, ny);
+                let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+                assert!((1.0..1.2).contains(&distance), "{} {}", nx, ny);
+            }
+            neighbours.sort();
+            neighbours.dedup();
+            assert_eq!(neighbours.len(), 6);
+        }
+    }
+
+    #[test]
+    fn cell_at_inverts_position() {
+        for grid in Grid::ALL {
+            let topology = grid.topology();
+            for y in 0..6 {
+                for x in 0..6 {
+                    let (px, py) = topology.position(x, y);
+                    assert_eq!(topology.cell_at(px + 0.5, py + 0.5), (x, y));
+                    assert_eq!(topology.cell_at(px + 0.01, py + 0.99), (x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hex_arrow_keys_go_up_and_down_on_the_side_the_snake_heads_to() {
+        let hex = Grid::Hex.topology();
+        assert!(hex.steer(Direction::Right, Direction::Up) == Some(Direction::UpRight));
+        assert!(hex.steer(Direction::Left, Direction::Up) == Some(Direction::UpLeft));
+        assert!(hex.steer(Direction::DownRight, Direction::Down) == Some(Direction::DownRight));
+        assert!(hex.steer(Direction::UpLeft, Direction::Down) == Some(Direction::DownLeft));
+        assert!(hex.steer(Direction::Right, Direction::Left) == Some(Direction::Left));
+        assert!(Grid::Square
+            .topology()
+            .steer(Direction::Up, Direction::UpLeft)
+            .is_none());
+    }
+
+    #[test]
+    fn only_the_wrapping_board_wraps() {
+        let wrap = Grid::Wrap.topology();
+        assert_eq!(wrap.neighbour(1, 1, Direction::Left, 20, 10), (18, 1));
+        assert_eq!(wrap.neighbour(18, 8, Direction::Down, 20, 10), (18, 1));
+        let square = Grid::Square.topology();
+        assert_eq!(square.neighbour(1, 1, Direction::Left, 20, 10), (0, 1));
+    }
+}
@@ -0,0 +1,796 @@
+use crate::death::{Death, DeathCause};
+use crate::enemy::Enemy;
+use crate::events::GameEvent;
+use crate::food::FoodKind;
+use crate::level::{teleport, Layout};
+use crate::mode::Rules;
+use crate::snake::{Direction, Snake, BOOST_FACTOR};
+use crate::topology::{Grid, Topology};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+
+/// With the hunger rule, the number of ticks the snake can go without eating before it
+/// loses a block of its tail.
+pub const HUNGER_TICKS: u32 = 100;
+
+/// The number of ticks between two blocks a boosting snake sheds.
+const BOOST_SHED_TICKS: u32 = 5;
+
+/// A snake this short can no longer boost, and stops if it is boosting.
+const MIN_BOOST_LENGTH: usize = 3;
+
+/// With the hunger rule, a snake this short starves instead of losing another block.
+const MIN_LENGTH: usize = 2;
+
+/// The rules of the game: the board, the snake, the food and the score, without any
+/// timing, input handling or drawing.
+///
+/// A world only changes through `step`, one tick at a time, and all of its randomness
+/// comes from its own seeded generator. The same seed, layout and moves therefore
+/// always give the same game, which is what replays and the daily challenge rely on.
+/// Enemies move on the same ticks as the snake, so they are as predictable as the rest.
+/// The `Rules` of the game mode are part of the world too, and have their say at the end
+/// of every tick, as does the optional hunger rule. Cloning a world is cheap enough to
+/// keep snapshots of it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct World {
+    pub snake: Snake,
+    pub score: u32,
+    /// The number of foods the snake has eaten, not counting the food it dropped.
+    pub food_eaten: u32,
+
+    pub food_exists: bool,
+    pub food_x: i32,
+    pub food_y: i32,
+    pub food_kind: FoodKind,
+
+    pub width: i32,
+    pub height: i32,
+    pub grid: Grid,
+    pub layout: Layout,
+    pub enemies: Vec<Enemy>,
+    pub rules: Rules,
+    pub hunger: bool,
+    /// The number of ticks since the snake last ate or lost a block to hunger.
+    pub hungry_ticks: u32,
+    /// The food left behind by the snake while boosting, which is eaten without points.
+    pub dropped: Vec<(i32, i32)>,
+    /// The number of ticks the snake has boosted since it last shed a block.
+    boost_ticks: u32,
+
+    pub ticks: u32,
+    /// The number of ticks the snake moved on while boosting, each of which lasts
+    /// `BOOST_FACTOR` times less than the others.
+    pub boosted_ticks: u32,
+    rng: Pcg32,
+}
+
+impl World {
+    /// Creates the world of a new game.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board, including the walls.
+    /// * `height` - The height of the board, including the walls.
+    /// * `grid` - The `Grid` of the board.
+    /// * `seed` - The seed of the generator that places the food.
+    /// * `layout` - The obstacles, portals, starting enemies, spawn and food spots.
+    ///
+    /// # Returns
+    ///
+    /// A `World` with the snake's tail on the spawn of the layout and the first food where
+    /// the layout puts it. With the default layout the snake starts at position (2, 2)
+    /// and the food at position (6, 4). The world is played by the endless rules, without
+    /// hunger, until `rules` and `hunger` are set otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// assert_eq!(world.score, 0);
+    /// assert!(world.food_exists);
+    /// ```
+    pub fn new(width: i32, height: i32, grid: Grid, seed: u64, layout: Layout) -> World {
+        let (spawn_x, spawn_y) = layout.spawn;
+        let (food_x, food_y) = layout.first_food();
+        World {
+            snake: Snake::new(spawn_x, spawn_y),
+            score: 0,
+            food_eaten: 0,
+            food_exists: true,
+            food_x,
+            food_y,
+            food_kind: FoodKind::Normal,
+            width,
+            height,
+            grid,
+            enemies: layout.enemies.clone(),
+            layout,
+            rules: Rules::default(),
+            hunger: false,
+            hungry_ticks: 0,
+            dropped: Vec::new(),
+            boost_ticks: 0,
+            ticks: 0,
+            boosted_ticks: 0,
+            rng: Pcg32::seed_from_u64(seed),
+        }
+    }
+
+    /// Advances the world by one tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - An optional `Direction` to turn the snake to before it moves. If `None`,
+    ///   the snake keeps its current direction.
+    ///
+    /// # Returns
+    ///
+    /// The events of the tick if the snake survived it, or the `Death` describing what
+    /// it ran into. The enemies move first, then the snake, which is not moved on the
+    /// tick it dies. Last, the game mode updates the world and checks whether the game is
+    /// over by its rules, which ends it after the snake moved.
+    ///
+    /// The events are, in order, a `GameEvent::DirectionChanged` when the snake turns, and
+    /// a `GameEvent::FoodEaten` when it eats, followed by `GameEvent::BoardCleared` if it
+    /// now fills every free cell. New food is placed in the same tick the food is eaten.
+    /// Eating food the snake dropped adds a `GameEvent::DroppedFoodEaten` instead.
+    /// With the hunger rule, a `GameEvent::TailLost` follows when the snake went hungry,
+    /// see `check_hunger`, and a `GameEvent::TailShed` when a boosting snake sheds a block,
+    /// see `shed_tail`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// assert!(world.step(Some(Direction::Down)).is_ok());
+    /// assert_eq!(world.ticks, 1);
+    /// ```
+    pub fn step(&mut self, dir: Option<Direction>) -> Result<Vec<GameEvent>, Death> {
+        self.ticks += 1;
+        if self.snake.is_boosting() {
+            self.boosted_ticks += 1;
+        }
+        self.move_enemies()?;
+        self.check_if_snake_alive(dir)?;
+
+        let mut events = Vec::new();
+        let from = self.snake.head_direction();
+        if let Some(to) = dir.filter(|&d| d != from) {
+            events.push(GameEvent::DirectionChanged { from, to });
+        }
+
+        self.snake.move_forward(
+            dir,
+            self.topology(),
+            self.width,
+            self.height,
+            &self.layout.portals,
+        );
+        self.check_eating(&mut events);
+        self.check_hunger(&mut events)?;
+        self.shed_tail(&mut events);
+
+        let rules = self.rules;
+        rules.mode().update(self);
+        rules.mode().check(self)?;
+        Ok(events)
+    }
+
+    /// Returns how long the game has lasted, in ticks at the pace of the game.
+    ///
+    /// # Returns
+    ///
+    /// The number of ticks, with the ticks the snake moved on while boosting counted
+    /// for the fraction of a tick they lasted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// world.ticks = 10;
+    /// world.boosted_ticks = 4;
+    /// assert_eq!(world.elapsed(), 8.0);
+    /// ```
+    pub fn elapsed(&self) -> f64 {
+        let saved = 1.0 - 1.0 / BOOST_FACTOR;
+        self.ticks as f64 - self.boosted_ticks as f64 * saved
+    }
+
+    /// Returns the `Topology` of the board.
+    pub fn topology(&self) -> &'static dyn Topology {
+        self.grid.topology()
+    }
+
+    /// Checks whether a cell holds an obstacle of the layout or a wall of the game mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    pub fn is_obstacle(&self, x: i32, y: i32) -> bool {
+        self.layout.is_obstacle(x, y) || self.rules.mode().is_wall(self, x, y)
+    }
+
+    /// Moves the food and removes the enemies that are on an obstacle, after the game
+    /// mode put up new walls. The food is moved as if it had been eaten.
+    pub fn clear_obstacles(&mut self) {
+        let enemies = std::mem::take(&mut self.enemies);
+        self.enemies = enemies
+            .into_iter()
+            .filter(|enemy| !self.is_obstacle(enemy.x, enemy.y))
+            .collect();
+
+        if self.food_exists && self.is_obstacle(self.food_x, self.food_y) {
+            self.food_exists = false;
+            self.add_food();
+        }
+
+        let dropped = std::mem::take(&mut self.dropped);
+        self.dropped = dropped
+            .into_iter()
+            .filter(|&(x, y)| !self.is_obstacle(x, y))
+            .collect();
+    }
+
+    /// Starts or stops boosting the snake, which then moves `BOOST_FACTOR` times faster
+    /// and sheds a block of its tail as food every `BOOST_SHED_TICKS` ticks.
+    ///
+    /// # Arguments
+    ///
+    /// * `boosting` - `true` to start boosting, `false` to stop.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the snake started or stopped boosting. A snake of `MIN_BOOST_LENGTH`
+    /// blocks or fewer cannot start.
+    ///
+    /// The boost is part of the world, so changing it has to be recorded like a turn for
+    /// a replay to play out the same. Hunger is counted in ticks, so a boosting snake also
+    /// goes hungry faster.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let mut world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// assert!(!world.set_boosting(true));
+    /// assert!(!world.snake.is_boosting());
+    /// ```
+    pub fn set_boosting(&mut self, boosting: bool) -> bool {
+        let boosting = boosting && self.snake.len() > MIN_BOOST_LENGTH;
+        if boosting == self.snake.is_boosting() {
+            return false;
+        }
+
+        self.snake.set_boosting(boosting);
+        true
+    }
+
+    /// Checks whether an enemy is in a cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The x-coordinate of the cell.
+    /// * `y` - The y-coordinate of the cell.
+    pub fn is_enemy(&self, x: i32, y: i32) -> bool {
+        self.enemies
+            .iter()
+            .any(|enemy| enemy.x == x && enemy.y == y)
+    }
+
+    /// Returns the cells the snake's head can get to from where it is, moving through
+    /// portals and around walls and obstacles. The snake's body and the enemies are not
+    /// in the way, since they move.
+    pub fn reachable_cells(&self) -> Vec<(i32, i32)> {
+        let topology = self.topology();
+        let mut reached = vec![self.snake.head_position()];
+        let mut index = 0;
+
+        while let Some(&(x, y)) = reached.get(index) {
+            index += 1;
+            for &dir in topology.directions() {
+                let cell = topology.neighbour(x, y, dir, self.width, self.height);
+                let (x, y) = teleport(&self.layout.portals, cell);
+                if self.is_inside(x, y) && !self.is_obstacle(x, y) && !reached.contains(&(x, y)) {
+                    reached.push((x, y));
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Checks that the world could come out of a real game on a board of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board.
+    /// * `height` - The height of the board.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the board size matches, the layout fits inside the walls, the snake
+    /// is a contiguous body inside the walls and off the obstacles, the enemies are on
+    /// distinct free cells off the snake, the snake is not hungrier than the hunger rule
+    /// allows nor has boosted longer than it can without shedding, and the food and the
+    /// dropped food lie on distinct cells inside the walls and off the snake, the
+    /// obstacles, the portals and the enemies. Otherwise a message naming the first problem.
+    pub fn validate(&self, width: i32, height: i32) -> Result<(), String> {
+        if self.width != width || self.height != height {
+            return Err(format!(
+                "board is {}x{}, expected {}x{}",
+                self.width, self.height, width, height
+            ));
+        }
+
+        self.layout.validate(width, height)?;
+        self.snake
+            .validate(width, height, self.topology(), &self.layout.portals)?;
+
+        for &(x, y) in &self.layout.obstacles {
+            if self.snake.contains(x, y) {
+                return Err(format!("obstacle at ({}, {}) is on the snake", x, y));
+            }
+        }
+
+        for (index, enemy) in self.enemies.iter().enumerate() {
+            let (x, y) = (enemy.x, enemy.y);
+            if !self.is_inside(x, y) || self.is_obstacle(x, y) || self.layout.is_portal(x, y) {
+                return Err(format!("enemy at ({}, {}) is not on a free cell", x, y));
+            }
+            if self.snake.contains(x, y) {
+                return Err(format!("enemy at ({}, {}) is on the snake", x, y));
+            }
+            if self.enemies[..index]
+                .iter()
+                .any(|other| (other.x, other.y) == (x, y))
+            {
+                return Err(format!("two enemies are at ({}, {})", x, y));
+            }
+        }
+
+        if self.boosted_ticks > self.ticks {
+            return Err(format!(
+                "boosted for {} ticks out of {}",
+                self.boosted_ticks, self.ticks
+            ));
+        }
+
+        if self.boost_ticks >= BOOST_SHED_TICKS {
+            return Err(format!(
+                "boosted for {} ticks without shedding, at most {} allowed",
+                self.boost_ticks,
+                BOOST_SHED_TICKS - 1
+            ));
+        }
+
+        for (index, &(x, y)) in self.dropped.iter().enumerate() {
+            let taken = self.snake.contains(x, y)
+                || self.is_obstacle(x, y)
+                || self.layout.is_portal(x, y)
+                || self.is_enemy(x, y)
+                || (self.food_exists && (self.food_x, self.food_y) == (x, y));
+            if !self.is_inside(x, y) || taken {
+                return Err(format!(
+                    "dropped food at ({}, {}) is not on a free cell",
+                    x, y
+                ));
+            }
+            if self.dropped[..index].contains(&(x, y)) {
+                return Err(format!("two dropped foods are at ({}, {})", x, y));
+            }
+        }
+
+        if self.hungry_ticks >= HUNGER_TICKS {
+            return Err(format!(
+                "hungry for {} ticks, at most {} allowed",
+                self.hungry_ticks,
+                HUNGER_TICKS - 1
+            ));
+        }
+
+        if self.food_exists {
+            let (x, y) = (self.food_x, self.food_y);
+            if !self.is_inside(x, y) {
+                return Err(format!("food at ({}, {}) is outside the board", x, y));
+            }
+            if !self.is_food_cell(x, y) {
+                return Err(format!("food at ({}, {}) is not on a free cell", x, y));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of cells inside the walls that are not obstacles, walls of the
+    /// game mode, portals or enemies, which the snake can fill. Each cell is counted once,
+    /// so an obstacle the walls of the game mode closed over is not taken away twice.
+    fn free_cells(&self) -> usize {
+        let open = (1..self.height - 1)
+            .flat_map(|y| (1..self.width - 1).map(move |x| (x, y)))
+            .filter(|&(x, y)| !self.is_obstacle(x, y) && !self.layout.is_portal(x, y))
+            .count();
+        open.saturating_sub(self.enemies.len())
+    }
+
+    /// Checks whether food can be placed on a cell inside the walls, which must not hold
+    /// the snake, an obstacle, a portal, an enemy or dropped food.
+    fn is_food_cell(&self, x: i32, y: i32) -> bool {
+        !self.snake.overlap_tail(x, y)
+            && !self.is_obstacle(x, y)
+            && !self.layout.is_portal(x, y)
+            && !self.is_enemy(x, y)
+            && !self.is_dropped(x, y)
+    }
+
+    /// Checks whether the snake dropped food on a cell while boosting.
+    fn is_dropped(&self, x: i32, y: i32) -> bool {
+        self.dropped.contains(&(x, y))
+    }
+
+    /// Checks whether an enemy can step onto a cell, which must lie inside the walls and
+    /// not hold the snake, the food, an obstacle, a portal or another enemy.
+    fn is_enemy_cell(&self, x: i32, y: i32) -> bool {
+        let is_food =
+            (self.food_exists && (self.food_x, self.food_y) == (x, y)) || self.is_dropped(x, y);
+        let taken = is_food
+            || self.snake.contains(x, y)
+            || self.is_obstacle(x, y)
+            || self.layout.is_portal(x, y)
+            || self.is_enemy(x, y);
+        self.is_inside(x, y) && !taken
+    }
+
+    /// Moves every enemy one step, in the order they are listed.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or the `Death` of the snake if an enemy moved onto its head. Apart from
+    /// the head, enemies only step onto cells allowed by `is_enemy_cell`.
+    fn move_enemies(&mut self) -> Result<(), Death> {
+        let head = self.snake.head_position();
+        for index in 0..self.enemies.len() {
+            let is_free = |(x, y): (i32, i32)| (x, y) == head || self.is_enemy_cell(x, y);
+            let moved = self.enemies[index].next(
+                self.ticks,
+                head,
+                self.topology(),
+                self.width,
+                self.height,
+                &is_free,
+            );
+            self.enemies[index] = moved;
+
+            if (moved.x, moved.y) == head {
+                let cause = DeathCause::Enemy;
+                return Err(Death {
+                    cause,
+                    x: moved.x,
+                    y: moved.y,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether a cell lies inside the walls.
+    fn is_inside(&self, x: i32, y: i32) -> bool {
+        x > 0 && y > 0 && x < self.width - 1 && y < self.height - 1
+    }
+
+    /// Checks if the snake's head is at the position of the food.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events of the current tick, to add to.
+    ///
+    /// If the snake's head is at the same position as the food, this function:
+    /// - Calls `restore_tail` on the snake to make it grow.
+    /// - Increases the score by the points of the food in the game mode, and counts the
+    ///   food.
+    /// - Adds a `GameEvent::FoodEaten` event, followed by `GameEvent::BoardCleared` if
+    ///   the snake now fills every free cell.
+    /// - Places new food.
+    ///
+    /// Food the snake dropped while boosting makes it grow back and adds a
+    /// `GameEvent::DroppedFoodEaten` event instead. It is worth no points, is not
+    /// replaced, and does not count as eating for the hunger rule or achievements.
+    fn check_eating(&mut self, events: &mut Vec<GameEvent>) {
+        let (head_x, head_y): (i32, i32) = self.snake.head_position();
+        if let Some(index) = self
+            .dropped
+            .iter()
+            .position(|&cell| cell == (head_x, head_y))
+        {
+            self.dropped.swap_remove(index);
+            self.snake.restore_tail();
+            events.push(GameEvent::DroppedFoodEaten {
+                x: head_x,
+                y: head_y,
+                length: self.snake.len(),
+            });
+            return;
+        }
+
+        if self.food_exists && self.food_x == head_x && self.food_y == head_y {
+            self.food_exists = false;
+            self.snake.restore_tail();
+            self.score += self.rules.mode().points(self, self.food_kind);
+            self.food_eaten += 1;
+            events.push(GameEvent::FoodEaten {
+                x: head_x,
+                y: head_y,
+                score: self.score,
+                length: self.snake.len(),
+            });
+
+            if self.snake.len() >= self.free_cells() {
+                events.push(GameEvent::BoardCleared { score: self.score });
+            }
+            self.add_food();
+        }
+    }
+
+    /// Makes the snake hungrier, with the hunger rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events of the current tick, to add to.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or the `Death` of the snake if it starved.
+    ///
+    /// Eating resets the hunger. Otherwise, once the snake has gone `HUNGER_TICKS` ticks
+    /// without eating, it loses the last block of its tail, adding a `GameEvent::TailLost`
+    /// event, and the count starts over. A snake that is down to `MIN_LENGTH` blocks
+    /// starves instead.
+    fn check_hunger(&mut self, events: &mut Vec<GameEvent>) -> Result<(), Death> {
+        if !self.hunger {
+            return Ok(());
+        }
+
+        let ate = events
+            .iter()
+            .any(|event| matches!(event, GameEvent::FoodEaten { .. }));
+        if ate {
+            self.hungry_ticks = 0;
+            return Ok(());
+        }
+
+        self.hungry_ticks += 1;
+        if self.hungry_ticks < HUNGER_TICKS {
+            return Ok(());
+        }
+
+        self.hungry_ticks = 0;
+        if self.snake.len() <= MIN_LENGTH {
+            let (x, y) = self.snake.head_position();
+            let cause = DeathCause::Starved;
+            return Err(Death { cause, x, y });
+        }
+
+        self.snake.trim_tail();
+        events.push(GameEvent::TailLost {
+            length: self.snake.len(),
+        });
+        Ok(())
+    }
+
+    /// Makes a boosting snake pay for its speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The events of the current tick, to add to.
+    ///
+    /// Every `BOOST_SHED_TICKS` ticks of boosting, the snake loses the last block of its
+    /// tail, which is left on the board as food, adding a `GameEvent::TailShed` event. Once
+    /// the snake is down to `MIN_BOOST_LENGTH` blocks it stops boosting.
+    fn shed_tail(&mut self, events: &mut Vec<GameEvent>) {
+        if !self.snake.is_boosting() {
+            return;
+        }
+
+        self.boost_ticks += 1;
+        if self.boost_ticks >= BOOST_SHED_TICKS {
+            self.boost_ticks = 0;
+            if let Some((x, y)) = self.snake.trim_tail() {
+                self.dropped.push((x, y));
+                events.push(GameEvent::TailShed {
+                    x,
+                    y,
+                    length: self.snake.len(),
+                });
+            }
+        }
+
+        if self.snake.len() <= MIN_BOOST_LENGTH {
+            self.snake.set_boosting(false);
+        }
+    }
+
+    /// Checks if the snake is alive based on its next head position.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - An optional `Direction` indicating the direction in which the snake will move next.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the snake survives the move, or the `Death` describing what the snake
+    /// would run into and in which cell. A head stepping onto a portal is checked at the
+    /// linked tile it comes out of. The snake dies if:
+    /// - Its next head position overlaps with its tail.
+    /// - Its next head position is outside the boundaries of the game area, which never
+    ///   happens on a board that wraps around.
+    /// - Its next head position holds an obstacle.
+    /// - Its next head position holds an enemy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let world = World::new(20, 20, Grid::Square, 42, Layout::default());
+    /// assert!(world.check_if_snake_alive(Some(Direction::Down)).is_ok());
+    /// let death = world.check_if_snake_alive(Some(Direction::Up)).unwrap_err();
+    /// assert!(death.cause == DeathCause::Wall);
+    /// ```
+    fn check_if_snake_alive(&self, dir: Option<Direction>) -> Result<(), Death> {
+        let (x, y) = self.snake.next_head(
+            dir,
+            self.topology(),
+            self.width,
+            self.height,
+            &self.layout.portals,
+        );
+
+        if let Some(segment) = self.snake.overlap_index(x, y) {
+            let cause = DeathCause::OwnBody { segment };
+            return Err(Death { cause, x, y });
+        }
+
+        if !self.is_inside(x, y) {
+            let cause = DeathCause::Wall;
+            return Err(Death { cause, x, y });
+        }
+
+        if self.is_obstacle(x, y) {
+            let cause = DeathCause::Obstacle;
+            return Err(Death { cause, x, y });
+        }
+
+        if self.is_enemy(x, y) {
+            let cause = DeathCause::Enemy;
+            return Err(Death { cause, x, y });
+        }
+
+        Ok(())
+    }
+
+    /// Adds food at a random free position.
+    ///
+    /// This function draws random coordinates within the game area from the world's
+    /// random number generator and ensures that the food does not overlap with the
+    /// snake's tail, an obstacle, a portal, an enemy or dropped food. Once a valid position
+    /// is found, it sets the `food_x` and `food_y` coordinates and marks `food_exists` as
+    /// `true`. No food is added once the snake and the food it dropped while boosting fill
+    /// the board. On a layout with food spots, a random free food spot is picked instead,
+    /// unless they are all taken. The kind of the food is picked at random from the kinds
+    /// of the layout, without drawing from the generator when there is only one.
+    fn add_food(&mut self) {
+        if self.snake.len() + self.dropped.len() >= self.free_cells() {
+            return;
+        }
+
+        let foods = &self.layout.foods;
+        self.food_kind = match foods.len() {
+            0 => FoodKind::Normal,
+            1 => foods[0],
+            count => foods[self.rng.gen_range(0..count)],
+        };
+
+        let spots: Vec<(i32, i32)> = self
+            .layout
+            .food_spots
+            .iter()
+            .copied()
+            .filter(|&(x, y)| self.is_food_cell(x, y))
+            .collect();
+        if !spots.is_empty() {
+            (self.food_x, self.food_y) = spots[self.rng.gen_range(0..spots.len())];
+            self.food_exists = true;
+            return;
+        }
+
+        let mut new_x = self.rng.gen_range(1..self.width - 1);
+        let mut new_y = self.rng.gen_range(1..self.height - 1);
+        while !self.is_food_cell(new_x, new_y) {
+            new_x = self.rng.gen_range(1..self.width - 1);
+            new_y = self.rng.gen_range(1..self.height - 1);
+        }
+
+        self.food_x = new_x;
+        self.food_y = new_y;
+        self.food_exists = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enemy::EnemyKind;
+    use crate::mode::Survival;
+
+    fn hungry_world() -> World {
+        let mut world = World::new(20, 20, Grid::Square, 7, Layout::default());
+        world.hunger = true;
+        world.food_exists = false;
+        world
+    }
+
+    #[test]
+    fn eating_resets_the_hunger() {
+        let mut world = hungry_world();
+        (world.food_x, world.food_y, world.food_exists) = (5, 2, true);
+        world.hungry_ticks = HUNGER_TICKS - 1;
+
+        let events = world.step(None).ok().unwrap();
+        assert!(matches!(events[0], GameEvent::FoodEaten { length: 4, .. }));
+        assert_eq!(world.hungry_ticks, 0);
+        assert_eq!(world.snake.len(), 4);
+    }
+
+    #[test]
+    fn going_hungry_loses_a_block_and_starts_over() {
+        let mut world = hungry_world();
+        world.hungry_ticks = HUNGER_TICKS - 2;
+
+        assert!(world.step(None).ok().unwrap().is_empty());
+        assert_eq!(world.snake.len(), 3);
+
+        let events = world.step(None).ok().unwrap();
+        assert!(matches!(events[..], [GameEvent::TailLost { length: 2 }]));
+        assert_eq!(world.snake.len(), 2);
+        assert_eq!(world.hungry_ticks, 0);
+    }
+
+    #[test]
+    fn going_hungry_at_the_shortest_length_starves() {
+        let mut world = hungry_world();
+        world.snake.trim_tail();
+        world.hungry_ticks = HUNGER_TICKS - 1;
+
+        let death = world.step(None).err().unwrap();
+        assert!(matches!(death.cause, DeathCause::Starved));
+        assert_eq!((death.x, death.y), (5, 2));
+        assert_eq!(world.snake.len(), MIN_LENGTH);
+    }
+
+    #[test]
+    fn obstacles_under_the_walls_of_the_game_mode_are_counted_once() {
+        let layout = Layout {
+            obstacles: vec![(1, 1), (5, 5)],
+            ..Layout::default()
+        };
+        let mut world = World::new(20, 20, Grid::Square, 7, layout);
+        world.rules = Rules::Survival(Survival);
+        world.ticks = 150;
+
+        assert_eq!(world.free_cells(), 16 * 16 - 1);
+    }
+
+    #[test]
+    fn free_cells_never_go_below_zero() {
+        let mut world = World::new(20, 20, Grid::Square, 7, Layout::default());
+        let enemy = Enemy {
+            kind: EnemyKind::Patrol,
+            x: 5,
+            y: 5,
+            direction: Direction::Right,
+        };
+        world.enemies = vec![enemy; 400];
+
+        assert_eq!(world.free_cells(), 0);
+    }
+}